@@ -1,25 +1,27 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::prelude::*;
+use crate::Solution;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input_file = File::open("input/2019_23.txt")?;
-    let reader = BufReader::new(input_file);
+pub struct Day23;
 
-    let instructions: Vec<Instruction> = reader
-        .lines()
-        .map(|line| line.unwrap().parse())
-        .collect::<Result<_, _>>()?;
+impl Solution for Day23 {
+    type Input = Vec<Instruction>;
+    type Answer = u32;
 
-    let mut cpu = match advent_of_code::part() {
-        advent_of_code::Part::One => Computer::part1(instructions),
-        advent_of_code::Part::Two => Computer::part2(instructions),
-    };
-
-    cpu.run();
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parser::parse(input, parser::lines(instruction))?)
+    }
 
-    println!("{}", cpu.reg_b);
+    fn part1(instructions: &Self::Input) -> Result<Self::Answer, AocError> {
+        let mut cpu = Computer::part1(instructions.clone());
+        cpu.run();
+        Ok(cpu.reg_b)
+    }
 
-    Ok(())
+    fn part2(instructions: &Self::Input) -> Result<Self::Answer, AocError> {
+        let mut cpu = Computer::part2(instructions.clone());
+        cpu.run();
+        Ok(cpu.reg_b)
+    }
 }
 
 struct Computer {
@@ -65,7 +67,7 @@ impl Computer {
                     ip = ((ip as isize) + offset) as usize;
                 }
                 Instruction::JumpIfEven(ref reg, offset) => {
-                    if self.get(reg) % 2 == 0 {
+                    if self.get(reg).is_multiple_of(2) {
                         ip = ((ip as isize) + offset) as usize;
                     } else {
                         ip += 1;
@@ -98,25 +100,22 @@ impl Computer {
 }
 
 #[derive(Clone)]
-enum Register {
+pub enum Register {
     A,
     B,
 }
 
-impl std::str::FromStr for Register {
-    type Err = InstructionParseError;
+fn register(input: &str) -> PResult<'_, Register> {
+    let register = parser::char_where(|c| c == 'a' || c == 'b', "register 'a' or 'b'");
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "a" => Ok(Register::A),
-            "b" => Ok(Register::B),
-            _ => Err(InstructionParseError::InvalidRegister),
-        }
-    }
+    parser::map(register, |c| match c {
+        'a' => Register::A,
+        _ => Register::B,
+    })(input)
 }
 
 #[derive(Clone)]
-enum Instruction {
+pub enum Instruction {
     Halve(Register),
     Triple(Register),
     Increment(Register),
@@ -125,47 +124,39 @@ enum Instruction {
     JumpIfOne(Register, isize),
 }
 
-impl std::str::FromStr for Instruction {
-    type Err = InstructionParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match &s[0..3] {
-            "hlf" => Ok(Instruction::Halve(s[4..].parse()?)),
-            "tpl" => Ok(Instruction::Triple(s[4..].parse()?)),
-            "inc" => Ok(Instruction::Increment(s[4..].parse()?)),
-            "jmp" => Ok(Instruction::Jump(
-                s[4..]
-                    .parse()
-                    .map_err(|_| InstructionParseError::InvalidJumpOffest)?,
-            )),
-            "jie" => Ok(Instruction::JumpIfEven(
-                s[4..5].parse()?,
-                s[7..]
-                    .parse()
-                    .map_err(|_| InstructionParseError::InvalidJumpOffest)?,
-            )),
-            "jio" => Ok(Instruction::JumpIfOne(
-                s[4..5].parse()?,
-                s[7..]
-                    .parse()
-                    .map_err(|_| InstructionParseError::InvalidJumpOffest)?,
-            )),
-            _ => Err(InstructionParseError::InvalidInstruction),
-        }
+/// `<opcode> <arguments>`, where the arguments depend on the opcode.
+fn instruction(input: &str) -> PResult<'_, Instruction> {
+    let (opcode, rest) = parser::terminated(
+        parser::take_while1(char::is_alphabetic, "an opcode"),
+        parser::literal(" "),
+    )(input)?;
+
+    let offset = parser::integer::<isize>();
+    let conditional = parser::pair(register, parser::preceded(parser::literal(", "), offset));
+
+    match opcode {
+        "hlf" => parser::map(register, Instruction::Halve)(rest),
+        "tpl" => parser::map(register, Instruction::Triple)(rest),
+        "inc" => parser::map(register, Instruction::Increment)(rest),
+        "jmp" => parser::map(parser::integer(), Instruction::Jump)(rest),
+        "jie" => parser::map(conditional, |(reg, off)| Instruction::JumpIfEven(reg, off))(rest),
+        "jio" => parser::map(conditional, |(reg, off)| Instruction::JumpIfOne(reg, off))(rest),
+        _ => Err(parser::Failure::new(
+            input,
+            format!("unknown instruction '{}'", opcode),
+        )),
     }
 }
-#[allow(clippy::enum_variant_names)]
-#[derive(Debug)]
-enum InstructionParseError {
-    InvalidInstruction,
-    InvalidJumpOffest,
-    InvalidRegister,
-}
 
-impl std::fmt::Display for InstructionParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "couldn't parse instruction: {:?}", self)
+impl std::str::FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse(s, instruction)
     }
 }
 
-impl std::error::Error for InstructionParseError {}
+crate::examples!(Day23 {
+    part1: ("2019_23_1", One, 4),
+    part2: ("2019_23_1", Two, 2),
+});
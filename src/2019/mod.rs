@@ -0,0 +1,9 @@
+use crate::Day;
+
+pub mod day23;
+
+pub const DAYS: &[Day] = &[Day {
+    year: 2019,
+    day: 23,
+    solution: &day23::Day23,
+}];
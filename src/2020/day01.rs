@@ -1,32 +1,126 @@
-use advent_of_code::prelude::*;
-
-const INPUT: &str = include_str!("../../input/2020_01.txt");
-
-fn main() {
-    let numbers: Vec<i32> = INPUT
-        .lines()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .or_exit_with("couldn't parse input");
-
-    let result =
-        match advent_of_code::part() {
-            advent_of_code::Part::One => numbers
-                .combinations::<CombineTwice>()
-                .find_map(|(n1, n2)| if n1 + n2 == 2020 { Some(n1 * n2) } else { None }),
-            advent_of_code::Part::Two => {
-                numbers
-                    .combinations::<CombineThreeTimes>()
-                    .find_map(|(n1, n2, n3)| match n1 + n2 + n3 == 2020 {
-                        true => Some(n1 * n2 * n3),
-                        false => None,
-                    })
+use crate::prelude::*;
+use crate::Solution;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+    type Answer = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parser::parse(input, parser::lines(parser::integer()))?)
+    }
+
+    fn part1(numbers: &Self::Input) -> Result<Self::Answer, AocError> {
+        numbers
+            .combinations::<CombineTwice>()
+            .find_map(|(n1, n2)| if n1 + n2 == 2020 { Some(n1 * n2) } else { None })
+            .ok_or(AocError::NoSolution("no two entries sum to 2020"))
+    }
+
+    fn part2(numbers: &Self::Input) -> Result<Self::Answer, AocError> {
+        numbers
+            .combinations::<CombineThreeTimes>()
+            .find_map(|(n1, n2, n3)| match n1 + n2 + n3 == 2020 {
+                true => Some(n1 * n2 * n3),
+                false => None,
+            })
+            .ok_or(AocError::NoSolution("no three entries sum to 2020"))
+    }
+
+    fn alternatives() -> Vec<Alternative<Self>> {
+        vec![
+            Alternative {
+                name: "hash set",
+                part: Part::One,
+                solve: |numbers| pair_with_hash_set(numbers),
+            },
+            Alternative {
+                name: "two pointers",
+                part: Part::Two,
+                solve: |numbers| triple_with_two_pointers(numbers),
+            },
+        ]
+    }
+
+    /// About 200 entries with exactly one pair and one triple summing to 2020.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        loop {
+            let pair = rng.range(1..=1009) as i32;
+            let a = rng.range(1..=600) as i32;
+            let b = rng.range(1..=600) as i32;
+            let mut numbers = vec![pair, 2020 - pair, a, b, 2020 - a - b];
+
+            // Entries above 1010 never sum to 2020 with each other, so only the five entries
+            // above can form another pair or triple with them.
+            while numbers.len() < 200 {
+                let n = rng.range(1011..=2019) as i32;
+                if !numbers.contains(&n) && sums_to_2020(&numbers, n) == 0 {
+                    numbers.push(n);
+                }
             }
-        };
 
-    let solution = result.or_exit_with("no solution found");
+            if has_one_pair_and_triple(&numbers) {
+                rng.shuffle(&mut numbers);
+                let lines: Vec<_> = numbers.iter().map(i32::to_string).collect();
+                return Some(lines.join("\n") + "\n");
+            }
+        }
+    }
+}
+
+fn pair_with_hash_set(numbers: &[i32]) -> Result<i32, AocError> {
+    let mut seen = std::collections::HashSet::new();
+
+    for &n in numbers {
+        if seen.contains(&(2020 - n)) {
+            return Ok(n * (2020 - n));
+        }
+        seen.insert(n);
+    }
 
-    println!("{}", solution);
+    Err(AocError::NoSolution("no two entries sum to 2020"))
+}
+
+fn triple_with_two_pointers(numbers: &[i32]) -> Result<i32, AocError> {
+    let mut sorted = numbers.to_vec();
+    sorted.sort_unstable();
+
+    for (i, &n1) in sorted.iter().enumerate() {
+        let (mut low, mut high) = (i + 1, sorted.len().saturating_sub(1));
+
+        while low < high {
+            match n1 + sorted[low] + sorted[high] {
+                sum if sum < 2020 => low += 1,
+                sum if sum > 2020 => high -= 1,
+                _ => return Ok(n1 * sorted[low] * sorted[high]),
+            }
+        }
+    }
+
+    Err(AocError::NoSolution("no three entries sum to 2020"))
+}
+
+/// Number of pairs and triples `n` would sum to 2020 with.
+fn sums_to_2020(numbers: &[i32], n: i32) -> usize {
+    let pairs = numbers.iter().filter(|&&m| n + m == 2020).count();
+    let triples = numbers
+        .combinations::<CombineTwice>()
+        .filter(|(m1, m2)| n + *m1 + *m2 == 2020)
+        .count();
+    pairs + triples
+}
+
+fn has_one_pair_and_triple(numbers: &[i32]) -> bool {
+    let pairs = numbers
+        .combinations::<CombineTwice>()
+        .filter(|(n1, n2)| *n1 + *n2 == 2020)
+        .count();
+    let triples = numbers
+        .combinations::<CombineThreeTimes>()
+        .filter(|(n1, n2, n3)| *n1 + *n2 + *n3 == 2020)
+        .count();
+    pairs == 1 && triples == 1
 }
 
 struct Combinations<'s, E, A> {
@@ -109,13 +203,13 @@ impl Default for CombineThreeTimes {
 }
 
 trait Combinable<'s, E> {
-    fn combinations<A>(&self) -> Combinations<E, A>
+    fn combinations<A>(&self) -> Combinations<'_, E, A>
     where
         A: Combiner<'s, E> + Default;
 }
 
 impl<'s, E> Combinable<'s, E> for [E] {
-    fn combinations<A>(&self) -> Combinations<E, A>
+    fn combinations<A>(&self) -> Combinations<'_, E, A>
     where
         A: Combiner<'s, E> + Default,
     {
@@ -126,6 +220,11 @@ impl<'s, E> Combinable<'s, E> for [E] {
     }
 }
 
+crate::examples!(Day01 {
+    part1: ("2020_01_1", One, 514579),
+    part2: ("2020_01_1", Two, 241861950),
+});
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -158,21 +257,4 @@ mod test {
         assert_eq!(iter.next(), Some((&3, &4, &5)));
         assert_eq!(iter.next(), None);
     }
-
-    #[test]
-    fn sample_input() {
-        let input = &[1721, 979, 366, 299, 675, 1456];
-
-        let result = input
-            .combinations::<CombineThreeTimes>()
-            .find_map(|(n1, n2, n3)| {
-                if n1 + n2 + n3 == 2020 {
-                    Some(n1 * n2 * n3)
-                } else {
-                    None
-                }
-            });
-
-        assert_eq!(result, Some(241861950));
-    }
 }
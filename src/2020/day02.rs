@@ -1,24 +1,53 @@
-use advent_of_code::prelude::*;
+use crate::prelude::*;
+use crate::Solution;
 
-const INPUT: &str = include_str!("../../input/2020_02.txt");
+pub struct Day02;
 
-fn main() {
-    let check_policy = match advent_of_code::part() {
-        advent_of_code::Part::One => Policy::is_part1_valid,
-        advent_of_code::Part::Two => Policy::is_part2_valid,
-    };
+impl Solution for Day02 {
+    type Input = Vec<Policy>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parser::parse(input, parser::lines(policy))?)
+    }
+
+    fn part1(policies: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(policies.iter().filter(|p| p.is_part1_valid()).count())
+    }
+
+    fn part2(policies: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(policies.iter().filter(|p| p.is_part2_valid()).count())
+    }
 
-    let solution = INPUT
-        .lines()
-        .map(|line| line.parse().or_exit_with("can't parse policy"))
-        .filter(check_policy)
-        .count();
+    /// 1000 policies whose passwords are biased towards their letter, so both rules match
+    /// a fair share of them.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        let letters: Vec<char> = ('a'..='z').collect();
+        let mut lines = Vec::with_capacity(1000);
 
-    println!("{}", solution);
+        for _ in 0..1000 {
+            let c = *rng.choose(&letters);
+            let min = rng.range(1..=10);
+            let max = rng.range(min + 1..=min + 10);
+            let password: String = (0..rng.range(1..=20))
+                .map(|_| {
+                    if rng.chance(0.3) {
+                        c
+                    } else {
+                        *rng.choose(&letters)
+                    }
+                })
+                .collect();
+
+            lines.push(format!("{}-{} {}: {}\n", min, max, c, password));
+        }
+
+        Some(lines.concat())
+    }
 }
 
 #[derive(Debug)]
-struct Policy {
+pub struct Policy {
     range: std::ops::RangeInclusive<usize>,
     password: String,
     c: char,
@@ -44,30 +73,56 @@ impl Policy {
 }
 
 impl std::str::FromStr for Policy {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(' ').collect();
-
-        match parts.as_slice() {
-            [range, c, password] => {
-                let parts: Vec<&str> = range.split('-').collect();
-                let range = match parts.as_slice() {
-                    [from, to] => {
-                        let from = from.parse().map_err(|_| "invalid from number")?;
-                        let to = to.parse().map_err(|_| "invalid to number")?;
-                        Ok(from..=to)
-                    }
-                    _ => Err("invalid range"),
-                }?;
+        parser::parse(s, policy)
+    }
+}
 
-                Ok(Policy {
-                    range,
-                    c: c.chars().next().unwrap(),
-                    password: (*password).to_owned(),
-                })
-            }
-            _ => Err("invalid format"),
-        }
+/// `<from>-<to> <char>: <password>`
+fn policy(input: &str) -> PResult<'_, Policy> {
+    use parser::{char_where, integer, literal, map_res, preceded, rest};
+
+    // Positions of part two start at 1.
+    let position = || {
+        map_res(integer(), |n: usize| match n {
+            0 => Err("expected a number of at least 1".to_owned()),
+            n => Ok(n),
+        })
+    };
+
+    let (from, input) = position()(input)?;
+    let (to, input) = preceded(literal("-"), position())(input)?;
+    let (c, input) = preceded(literal(" "), char_where(char::is_alphanumeric, "a letter"))(input)?;
+    let (password, input) = preceded(literal(": "), rest)(input)?;
+
+    let policy = Policy {
+        range: from..=to,
+        c,
+        password: password.to_owned(),
+    };
+
+    Ok((policy, input))
+}
+
+crate::examples!(Day02 {
+    part1: ("2020_02_1", One, 2),
+    part2: ("2020_02_1", Two, 1),
+});
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_position_zero() {
+        let err = "0-3 a: abc".parse::<Policy>().unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err = Day02::parse("1-3 a: abc\n2-0 b: abc\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("parse error at 2:3: expected a number of at least 1"));
     }
 }
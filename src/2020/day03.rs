@@ -1,102 +1,582 @@
-use advent_of_code::prelude::*;
-
-const INPUT: &str = include_str!("../../input/2020_03.txt");
-
-fn main() {
-    let map: Map = INPUT.parse().or_exit_with("can't parse input");
-
-    let trees = match advent_of_code::part() {
-        advent_of_code::Part::One => map.slope(3, 1).count_trees(),
-        advent_of_code::Part::Two => {
-            let mut slopes = [
-                map.slope(1, 1),
-                map.slope(3, 1),
-                map.slope(5, 1),
-                map.slope(7, 1),
-                map.slope(1, 2),
-            ];
-            slopes.iter_mut().map(Slope::count_trees).product()
-        }
-    };
+use crate::grid::{Grid, Wrap};
+use crate::prelude::*;
+use crate::Solution;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Map;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(map: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(map.slope(3, 1).count_trees())
+    }
+
+    fn part2(map: &Self::Input) -> Result<Self::Answer, AocError> {
+        let mut slopes = [
+            map.slope(1, 1),
+            map.slope(3, 1),
+            map.slope(5, 1),
+            map.slope(7, 1),
+            map.slope(1, 2),
+        ];
+        Ok(slopes.iter_mut().map(Slope::count_trees).product())
+    }
 
-    println!("{}", trees);
+    /// The bit packed map is built by the alternatives themselves, so their times include it.
+    fn alternatives() -> Vec<Alternative<Self>> {
+        vec![
+            Alternative {
+                name: "bit packed",
+                part: Part::One,
+                solve: |map| Ok(PackedMap::new(map).count_trees(3, 1)),
+            },
+            Alternative {
+                name: "bit packed",
+                part: Part::Two,
+                solve: |map| {
+                    let packed = PackedMap::new(map);
+                    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+                    Ok(slopes
+                        .iter()
+                        .map(|&(dir_x, dir_y)| packed.count_trees(dir_x, dir_y))
+                        .product())
+                },
+            },
+        ]
+    }
+
+    /// A map of the same size as the puzzle's.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        Some(random_map(rng, 31, 323))
+    }
 }
 
-enum Tile {
+/// A map with about a quarter of the tiles being trees.
+fn random_map(rng: &mut Rng, width: usize, height: usize) -> String {
+    let mut map = String::with_capacity((width + 1) * height);
+
+    for _ in 0..height {
+        map.extend((0..width).map(|_| if rng.chance(0.25) { '#' } else { '.' }));
+        map.push('\n');
+    }
+
+    map
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
     Empty,
     Tree,
 }
 
-struct Map {
-    tiles: Vec<Tile>,
-    width: usize,
+impl From<Tile> for char {
+    fn from(tile: Tile) -> char {
+        match tile {
+            Tile::Empty => '.',
+            Tile::Tree => '#',
+        }
+    }
 }
 
-impl Map {
-    fn get(&self, (x, y): (usize, usize)) -> Option<&Tile> {
-        self.tiles.get(y * self.width + (x % self.width))
-    }
+pub struct Map {
+    grid: Grid<Tile>,
+}
 
-    fn slope(&self, dir_x: usize, dir_y: usize) -> Slope {
+impl Map {
+    /// The slope of the puzzle: starting in the top left corner and repeating to the right.
+    pub fn slope(&self, dir_x: i64, dir_y: i64) -> Slope<'_> {
         Slope {
             map: self,
-            dir: (dir_x, dir_y),
+            start: (0, 0),
             pos: (0, 0),
+            dir: (dir_x, dir_y),
+            edge: Edge::WrapX,
+            done: false,
+        }
+    }
+}
+
+/// What happens to a slope at the edges of the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// The map repeats to the left and right, the slope ends above or below it.
+    WrapX,
+    /// The map repeats in every direction.
+    WrapBoth,
+    /// The slope ends at every edge.
+    Stop,
+    /// The slope is reflected by every edge.
+    Bounce,
+}
+
+impl std::str::FromStr for Edge {
+    type Err = String;
+
+    /// `wrap-x`, `wrap-both`, `stop` or `bounce`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrap-x" => Ok(Edge::WrapX),
+            "wrap-both" => Ok(Edge::WrapBoth),
+            "stop" => Ok(Edge::Stop),
+            "bounce" => Ok(Edge::Bounce),
+            _ => Err(format!("invalid edge '{}'", s)),
         }
     }
 }
 
-struct Slope<'map> {
-    dir: (usize, usize),
-    pos: (usize, usize),
+impl Edge {
+    /// How the map repeats for slopes with this edge, `None` when they bounce instead.
+    fn wrap(self) -> Option<Wrap> {
+        match self {
+            Edge::WrapX => Some(Wrap::X),
+            Edge::WrapBoth => Some(Wrap::Both),
+            Edge::Stop => Some(Wrap::None),
+            Edge::Bounce => None,
+        }
+    }
+}
+
+/// Distance after which positions along an axis of length `len` repeat when bouncing. Bouncing
+/// is wrapping around a map mirrored at its edge, as in a kaleidoscope.
+fn bounce_period(len: usize) -> i64 {
+    (2 * (len as i64 - 1)).max(1)
+}
+
+/// Position on an axis of length `len` that a bouncing slope is at after `n` steps.
+fn bounce(n: i64, len: usize) -> usize {
+    let n = n.rem_euclid(bounce_period(len)) as usize;
+    match n < len {
+        true => n,
+        false => 2 * (len - 1) - n,
+    }
+}
+
+/// Tiles a toboggan passes on its way across the map, together with their positions on it. The
+/// start is only part of the slope when the toboggan comes back to it.
+///
+/// Slopes that can't leave the map end with their start, once they're back there going the way
+/// they started. Wrapping slopes don't pass any tile twice before that, but bouncing ones can
+/// cross their own way, and pass their start on the way back.
+pub struct Slope<'map> {
     map: &'map Map,
+    start: (i64, i64),
+    /// Position as if the map were repeated, so it never gets reset by the edges.
+    pos: (i64, i64),
+    dir: (i64, i64),
+    edge: Edge,
+    done: bool,
 }
 
-impl Slope<'_> {
-    fn count_trees(&mut self) -> usize {
-        self.filter(|t| matches!(t, Tile::Tree)).count()
+impl<'map> Slope<'map> {
+    /// Panics when the position isn't on the map.
+    pub fn starting_at(mut self, (x, y): (usize, usize)) -> Self {
+        assert!(
+            self.map.grid.get((x, y)).is_some(),
+            "start isn't on the map"
+        );
+        self.start = (x as i64, y as i64);
+        self.pos = self.start;
+        self
+    }
+
+    pub fn with_edge(mut self, edge: Edge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    pub fn count_trees(&mut self) -> usize {
+        self.filter(|(_, t)| matches!(t, Tile::Tree)).count()
     }
 }
 
 impl<'map> Iterator for Slope<'map> {
-    type Item = &'map Tile;
+    type Item = ((usize, usize), &'map Tile);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.pos = (self.pos.0 + self.dir.0, self.pos.1 + self.dir.1);
-        self.map.get(self.pos)
+        if self.done || self.dir == (0, 0) {
+            return None;
+        }
+
+        let grid = &self.map.grid;
+        let pos = (self.pos.0 + self.dir.0, self.pos.1 + self.dir.1);
+        let start = (self.start.0 as usize, self.start.1 as usize);
+
+        let (on_map, done) = match self.edge.wrap() {
+            Some(wrap) => {
+                let on_map = grid.resolve(pos, wrap)?;
+                (on_map, on_map == start)
+            }
+            // A bouncing slope can pass its start going the other way, it's only back where it
+            // started after a whole period on both axes.
+            None => {
+                let (width, height) = (grid.width(), grid.height());
+                let on_map = (bounce(pos.0, width), bounce(pos.1, height));
+                let done = (pos.0 - self.start.0) % bounce_period(width) == 0
+                    && (pos.1 - self.start.1) % bounce_period(height) == 0;
+                (on_map, done)
+            }
+        };
+
+        self.pos = pos;
+        self.done = done;
+        Some((on_map, &grid[on_map]))
     }
 }
 
-impl std::str::FromStr for Map {
-    type Err = String;
+/// Slopes of the puzzle with the fewest and the most trees on their way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlopeRanking {
+    /// `((dir_x, dir_y), trees)`, fewest trees first.
+    pub fewest: Vec<((i64, i64), usize)>,
+    /// `((dir_x, dir_y), trees)`, most trees first.
+    pub most: Vec<((i64, i64), usize)>,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let width = s.find('\n').unwrap_or_else(|| s.len());
+/// Counts the trees of every puzzle slope going down whose direction is reduced and has no
+/// coordinate larger than `bound`, and keeps the `k` best and worst ones. Ties are broken by
+/// direction.
+pub fn rank_slopes(map: &Map, bound: i64, k: usize) -> SlopeRanking {
+    let mut slopes = slope_trees(map, bound);
+    slopes.sort_by_key(|&(dir, trees)| (trees, dir));
+
+    let fewest = slopes.iter().take(k).copied().collect();
+    slopes.sort_by_key(|&(dir, trees)| (std::cmp::Reverse(trees), dir));
+    let most = slopes.into_iter().take(k).collect();
+
+    SlopeRanking { fewest, most }
+}
+
+/// Trees on every slope [`rank_slopes`] considers, in no particular order.
+///
+/// Instead of walking every slope with [`Slope`], the trees on the rows that slopes going down
+/// by the same amount pass are tallied once, by column and by the number of the step reaching
+/// their row modulo the width. The column of a slope after a step only depends on that residue,
+/// so its trees are the sum of one tally per residue.
+pub fn slope_trees(map: &Map, bound: i64) -> Vec<((i64, i64), usize)> {
+    let (width, height) = (map.grid.width(), map.grid.height());
+    let mut slopes = Vec::new();
+
+    for dir_y in 1..=bound {
+        // `tallies[residue * width + x]`
+        let mut tallies = vec![0; width * width];
+        let rows = (dir_y as usize..height).step_by(dir_y as usize);
+        for (i, y) in (1..).zip(rows) {
+            let residue = i % width;
+            for (x, &tile) in map.grid.row(y).iter().enumerate() {
+                if tile == Tile::Tree {
+                    tallies[residue * width + x] += 1;
+                }
+            }
+        }
+
+        for dir_x in -bound..=bound {
+            if gcd(dir_x.abs(), dir_y) != 1 {
+                continue;
+            }
+
+            let step = dir_x.rem_euclid(width as i64) as usize;
+            let trees = (0..width)
+                .map(|residue| tallies[residue * width + residue * step % width])
+                .sum();
+            slopes.push(((dir_x, dir_y), trees));
+        }
+    }
+
+    slopes
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    match b {
+        0 => a,
+        b => gcd(b, a % b),
+    }
+}
+
+/// Tile of a [drawing](draw), with the first slope that passed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub tile: Tile,
+    /// Index of the slope.
+    pub slope: Option<usize>,
+}
+
+/// The map with the tiles every slope passes. Slopes that wrap horizontally get the map repeated
+/// to the left and right as often as they need.
+pub fn draw(map: &Map, slopes: Vec<Slope<'_>>) -> Grid<Cell> {
+    let width = map.grid.width() as i64;
+    let mut hits = Vec::new();
+
+    for (i, mut slope) in slopes.into_iter().enumerate() {
+        let wraps_x = matches!(slope.edge, Edge::WrapX | Edge::WrapBoth);
+
+        while let Some(((x, y), _)) = slope.next() {
+            let x = if wraps_x { slope.pos.0 } else { x as i64 };
+            hits.push(((x, y), i));
+        }
+    }
+
+    let first = hits.iter().map(|&((x, _), _)| x).min().unwrap_or(0).min(0);
+    let last = hits.iter().map(|&((x, _), _)| x).max().unwrap_or(0);
+    let copies = (first.div_euclid(width), last.div_euclid(width));
+    let offset = copies.0 * width;
+
+    let mut drawing = Grid::from_fn(
+        ((copies.1 - copies.0 + 1) * width) as usize,
+        map.grid.height(),
+        |(x, y)| Cell {
+            tile: map.grid[((x as i64 + offset).rem_euclid(width) as usize, y)],
+            slope: None,
+        },
+    );
+
+    for ((x, y), i) in hits {
+        let cell = &mut drawing[((x - offset) as usize, y)];
+        cell.slope.get_or_insert(i);
+    }
+
+    drawing
+}
+
+/// Colours of the slopes, used again when there are more slopes.
+const COLORS: [(u8, (u8, u8, u8)); 6] = [
+    (31, (220, 40, 40)),
+    (34, (40, 90, 220)),
+    (33, (230, 160, 0)),
+    (35, (190, 40, 190)),
+    (36, (0, 170, 190)),
+    (32, (60, 170, 40)),
+];
 
-        let mut tiles = Vec::with_capacity(s.len());
+/// Renders a drawing for terminals, marking trees a slope hit with `X` and open tiles it passed
+/// with `O` in the colour of the slope.
+pub fn ansi(drawing: &Grid<Cell>) -> String {
+    let mut out = String::new();
 
-        for (line_number, line) in s.lines().enumerate() {
-            if line.len() > width {
-                return Err(format!("line {} has different length", line_number + 1));
+    for row in drawing.rows() {
+        for cell in row {
+            match cell.slope {
+                Some(i) => {
+                    let mark = if cell.tile == Tile::Tree { 'X' } else { 'O' };
+                    out += &format!("\x1b[1;{}m{}\x1b[0m", COLORS[i % COLORS.len()].0, mark);
+                }
+                None => out.push(cell.tile.into()),
             }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Renders a drawing as a binary PPM image with `scale` pixels per tile. Trees are dark, and
+/// tiles a slope passed have its colour, lighter for open tiles.
+pub fn ppm(drawing: &Grid<Cell>, scale: usize) -> Vec<u8> {
+    let (width, height) = (drawing.width() * scale, drawing.height() * scale);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    image.reserve(width * height * 3);
 
-            for (cn, c) in line.char_indices() {
-                let tile = match c {
-                    '.' => Tile::Empty,
-                    '#' => Tile::Tree,
-                    _ => {
-                        return Err(format!(
-                            "invalid symbol at line {}:{}",
-                            line_number + 1,
-                            cn + 1
-                        ))
+    for row in drawing.rows() {
+        let pixels: Vec<u8> = row
+            .iter()
+            .flat_map(|cell| {
+                let (r, g, b) = match (cell.slope, cell.tile) {
+                    (Some(i), Tile::Tree) => COLORS[i % COLORS.len()].1,
+                    (Some(i), Tile::Empty) => {
+                        let (r, g, b) = COLORS[i % COLORS.len()].1;
+                        let lighter = |c: u8| c + (255 - c) / 2;
+                        (lighter(r), lighter(g), lighter(b))
                     }
+                    (None, Tile::Tree) => (30, 80, 30),
+                    (None, Tile::Empty) => (245, 245, 240),
                 };
+                [r, g, b].repeat(scale)
+            })
+            .collect();
+
+        for _ in 0..scale {
+            image.extend_from_slice(&pixels);
+        }
+    }
+
+    image
+}
+
+/// The trees of a map as one bit per tile, in rows of `u64` words, for counting trees without
+/// going through [`Tile`]s.
+pub struct PackedMap {
+    words: Vec<u64>,
+    words_per_row: usize,
+    width: usize,
+    height: usize,
+}
+
+impl PackedMap {
+    pub fn new(map: &Map) -> PackedMap {
+        let grid = &map.grid;
+        let words_per_row = grid.width().div_ceil(64);
+        let mut words = vec![0; words_per_row * grid.height()];
 
-                tiles.push(tile)
+        for ((x, y), tile) in grid.iter() {
+            if *tile == Tile::Tree {
+                words[y * words_per_row + x / 64] |= 1 << (x % 64);
             }
         }
 
-        Ok(Map { tiles, width })
+        PackedMap {
+            words,
+            words_per_row,
+            width: grid.width(),
+            height: grid.height(),
+        }
+    }
+
+    /// Same as `map.slope(dir_x, dir_y).count_trees()`, looking up every tile in the packed rows.
+    pub fn count_trees(&self, dir_x: i64, dir_y: i64) -> usize {
+        let step_x = dir_x.rem_euclid(self.width as i64) as usize;
+        let steps = match dir_y {
+            0 if dir_x == 0 => 0,
+            // A horizontal slope ends when it's back at the start.
+            0 => self.width / gcd(step_x as i64, self.width as i64) as usize,
+            dir_y if dir_y > 0 => (self.height - 1) / dir_y as usize,
+            _ => 0,
+        };
+
+        let (mut x, mut y) = (0, 0);
+        let mut trees = 0;
+
+        for _ in 0..steps {
+            x += step_x;
+            if x >= self.width {
+                x -= self.width;
+            }
+            y += dir_y as usize;
+
+            let word = self.words[y * self.words_per_row + x / 64];
+            trees += (word >> (x % 64) & 1) as usize;
+        }
+
+        trees
+    }
+}
+
+impl std::str::FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tile = |c| match c {
+            '#' => Some(Tile::Tree),
+            '.' => Some(Tile::Empty),
+            _ => None,
+        };
+
+        Ok(Map {
+            grid: Grid::parse(s, tile, "'.' or '#'")?,
+        })
+    }
+}
+
+crate::examples!(Day03 {
+    part1: ("2020_03_1", One, 7),
+    part2: ("2020_03_1", Two, 336),
+});
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn slope_trees_match_walking() {
+        let map: Map = include_str!("../../examples/2020_03_1.txt")
+            .parse()
+            .unwrap();
+
+        for ((dir_x, dir_y), trees) in slope_trees(&map, 13) {
+            assert_eq!(
+                map.slope(dir_x, dir_y).count_trees(),
+                trees,
+                "{} {}",
+                dir_x,
+                dir_y
+            );
+        }
+
+        let ranking = rank_slopes(&map, 3, 2);
+        assert_eq!(ranking.most[0], ((3, 1), 7));
+        assert!(ranking.fewest[0].1 <= ranking.fewest[1].1);
+        assert!(ranking.fewest.iter().all(|&(_, trees)| trees < 7));
+    }
+
+    fn positions(slope: Slope<'_>) -> Vec<(usize, usize)> {
+        slope.map(|(pos, _)| pos).collect()
+    }
+
+    #[test]
+    fn packed_counts_match_slopes() {
+        let mut rng = Rng::new(3);
+
+        for &(width, height) in &[(1, 5), (31, 323), (64, 100), (65, 70), (200, 1000)] {
+            let map: Map = random_map(&mut rng, width, height).parse().unwrap();
+            let packed = PackedMap::new(&map);
+
+            for dir_y in -1..=8 {
+                for dir_x in -70..=70 {
+                    assert_eq!(
+                        packed.count_trees(dir_x, dir_y),
+                        map.slope(dir_x, dir_y).count_trees(),
+                        "{}x{} map, slope {} {}",
+                        width,
+                        height,
+                        dir_x,
+                        dir_y
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn drawing() {
+        let map: Map = "..#\n#..\n.#.\n".parse().unwrap();
+        let drawing = draw(&map, vec![map.slope(2, 1), map.slope(-1, 1)]);
+
+        let plain = ansi(&drawing)
+            .replace("\x1b[1;31m", "")
+            .replace("\x1b[1;34m", "");
+        assert_eq!(
+            plain.replace("\x1b[0m", ""),
+            "..#..#..#\n#.O#.O#..\n.X..#..X.\n"
+        );
+
+        let image = ppm(&drawing, 2);
+        assert!(image.starts_with(b"P6\n18 6\n255\n"));
+        assert_eq!(image.len(), "P6\n18 6\n255\n".len() + 18 * 6 * 3);
+    }
+
+    #[test]
+    fn edges() {
+        let map: Map = "..#\n#..\n...\n".parse().unwrap();
+
+        assert_eq!(positions(map.slope(-1, 1)), [(2, 1), (1, 2)]);
+        assert_eq!(positions(map.slope(1, 0)), [(1, 0), (2, 0), (0, 0)]);
+        assert_eq!(
+            positions(map.slope(1, 2).with_edge(Edge::WrapBoth)),
+            [(1, 2), (2, 1), (0, 0)]
+        );
+        assert_eq!(
+            positions(map.slope(0, -1).starting_at((1, 2)).with_edge(Edge::Stop)),
+            [(1, 1), (1, 0)]
+        );
+        assert_eq!(
+            positions(map.slope(1, 1).with_edge(Edge::Bounce)),
+            [(1, 1), (2, 2), (1, 1), (0, 0)]
+        );
+        assert_eq!(map.slope(0, 0).count(), 0);
+        let mut bouncing = map.slope(2, 1).starting_at((0, 1)).with_edge(Edge::Bounce);
+        assert_eq!(bouncing.count_trees(), 3);
     }
 }
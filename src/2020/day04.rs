@@ -1,28 +1,118 @@
+use crate::prelude::*;
+use crate::Solution;
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../../input/2020_04.txt");
+pub struct Day04;
 
-fn main() {
-    let check_passport = match advent_of_code::part() {
-        advent_of_code::Part::One => PassportData::is_part1_valid,
-        advent_of_code::Part::Two => PassportData::is_part2_valid,
-    };
+impl Solution for Day04 {
+    type Input = Vec<PassportData>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parser::parse(input, parser::blocks(passport))?)
+    }
+
+    fn part1(passports: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(passports.iter().filter(|p| p.is_part1_valid()).count())
+    }
 
-    let valid_passports = PassportData::from_list(INPUT)
-        .filter(|p| check_passport(p))
-        .count();
+    fn part2(passports: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(passports.iter().filter(|p| p.is_part2_valid()).count())
+    }
 
-    println!("{}", valid_passports);
+    fn generate(rng: &mut Rng) -> Option<String> {
+        Some(passports(rng, 300, 0.5))
+    }
 }
 
-#[derive(Debug)]
-struct PassportData(HashMap<String, String>);
+/// A batch of `count` passports, each valid for part two with the given probability. Invalid
+/// passports either miss a required field or have one with a value that breaks its rule, so
+/// some of them still count for part one.
+pub fn passports(rng: &mut Rng, count: usize, valid_probability: f64) -> String {
+    let mut batch = Vec::with_capacity(count);
 
-impl PassportData {
-    fn from_list<'i>(input: &'i str) -> impl Iterator<Item = PassportData> + 'i {
-        input.split("\n\n").filter_map(|s| s.parse().ok())
+    for _ in 0..count {
+        let mut keys: Vec<&str> = REQUIRED_FIELDS.iter().map(|(key, _)| *key).collect();
+        if rng.chance(0.5) {
+            keys.push("cid");
+        }
+        rng.shuffle(&mut keys);
+
+        let invalid = match rng.chance(valid_probability) {
+            true => None,
+            false => Some(rng.range(0..=REQUIRED_FIELDS.len() as i64 - 1) as usize),
+        };
+        let missing = invalid.is_some() && rng.chance(0.5);
+
+        let mut passport = String::new();
+        for key in keys {
+            let valid = invalid.is_none_or(|i| REQUIRED_FIELDS[i].0 != key);
+            if !valid && missing {
+                continue;
+            }
+
+            if !passport.is_empty() {
+                passport.push(if rng.chance(0.3) { '\n' } else { ' ' });
+            }
+            passport += &format!("{}:{}", key, field_value(rng, key, valid));
+        }
+
+        batch.push(passport + "\n");
     }
 
+    batch.join("\n")
+}
+
+fn field_value(rng: &mut Rng, key: &str, valid: bool) -> String {
+    let digits =
+        |rng: &mut Rng, n| -> String { (0..n).map(|_| rng.range(0..=9).to_string()).collect() };
+    let year = |rng: &mut Rng, from, to| match valid {
+        true => rng.range(from..=to),
+        false if rng.chance(0.5) => rng.range(from - 20..=from - 1),
+        false => rng.range(to + 1..=to + 20),
+    };
+
+    match key {
+        "byr" => year(rng, 1920, 2002).to_string(),
+        "iyr" => year(rng, 2010, 2020).to_string(),
+        "eyr" => year(rng, 2020, 2030).to_string(),
+        "hgt" => match (valid, rng.chance(0.5)) {
+            (true, true) => format!("{}cm", rng.range(150..=193)),
+            (true, false) => format!("{}in", rng.range(59..=76)),
+            (false, true) => format!("{}cm", rng.range(194..=250)),
+            (false, false) => rng.range(59..=193).to_string(),
+        },
+        "hcl" => {
+            let code: String = (0..6)
+                .map(|_| *rng.choose(b"0123456789abcdef") as char)
+                .collect();
+            match valid {
+                true => format!("#{}", code),
+                false => code,
+            }
+        }
+        "ecl" => {
+            let colors: &[&str] = match valid {
+                true => &["amb", "blu", "brn", "gry", "grn", "hzl", "oth"],
+                false => &["red", "xyz", "zzz", "gmt"],
+            };
+            rng.choose(colors).to_string()
+        }
+        "pid" => match valid {
+            true => digits(rng, 9),
+            false => {
+                let len = if rng.chance(0.5) { 8 } else { 10 };
+                digits(rng, len)
+            }
+        },
+        _ => digits(rng, 3),
+    }
+}
+
+#[derive(Debug)]
+pub struct PassportData(HashMap<String, String>);
+
+impl PassportData {
     fn is_part1_valid(&self) -> bool {
         REQUIRED_FIELDS
             .iter()
@@ -69,50 +159,58 @@ fn is_valid_color(s: &str) -> bool {
     }
 }
 
+/// Whitespace separated `key:value` fields, possibly spread over several lines.
+fn passport(input: &str) -> PResult<'_, PassportData> {
+    let key = parser::take_while1(char::is_alphanumeric, "a field name");
+    let value = parser::take_while1(|c: char| !c.is_whitespace(), "a field value");
+    let field = parser::key_value(key, parser::literal(":"), value);
+    let separator = parser::take_while1(char::is_whitespace, "whitespace");
+
+    parser::map(parser::separated(field, separator), |fields| {
+        PassportData(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    })(input)
+}
+
 impl std::str::FromStr for PassportData {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut values = HashMap::new();
-
-        for pair in s.split_whitespace() {
-            let mut parts = pair.split(':');
-            match (parts.next(), parts.next()) {
-                (Some(key), Some(value)) => {
-                    values.insert(key.to_string(), value.to_string());
-                }
-                _ => return Err(format!("invalid passport: {:?}", s)),
-            }
-        }
-
-        Ok(PassportData(values))
+        parser::parse(s, passport)
     }
 }
 
+crate::examples!(Day04 {
+    part1: ("2020_04_1", One, 2),
+    part2_invalid: ("2020_04_2", Two, 0),
+    part2_valid: ("2020_04_3", Two, 4),
+});
+
 #[cfg(test)]
-mod tests {
+mod test {
     use super::*;
 
     #[test]
-    fn test() {
-        let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
-byr:1937 iyr:2017 cid:147 hgt:183cm
-
-iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
-hcl:#cfa07d byr:1929
-
-hcl:#ae17e1 iyr:2013
-eyr:2024
-ecl:brn pid:760753108 byr:1931
-hgt:179cm
-
-hcl:#cfa07d eyr:2025 pid:166559648
-iyr:2011 ecl:brn hgt:59in";
+    fn generated_validity() {
+        let mut rng = Rng::new(4);
+        let count = |batch: &str, part| {
+            let passports = Day04::parse(batch).unwrap();
+            match part {
+                Part::One => Day04::part1(&passports).unwrap(),
+                Part::Two => Day04::part2(&passports).unwrap(),
+            }
+        };
 
-        let valid_passports = PassportData::from_list(input)
-            .filter(|p| p.is_part1_valid())
-            .count();
+        let valid = passports(&mut rng, 50, 1.0);
+        assert_eq!(count(&valid, Part::One), 50);
+        assert_eq!(count(&valid, Part::Two), 50);
 
-        assert_eq!(valid_passports, 2);
+        let invalid = passports(&mut rng, 50, 0.0);
+        assert!(count(&invalid, Part::One) > 0);
+        assert_eq!(count(&invalid, Part::Two), 0);
     }
 }
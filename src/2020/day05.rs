@@ -1,41 +1,80 @@
-use advent_of_code::prelude::*;
-
-const INPUT: &str = include_str!("../../input/2020_05.txt");
-
-fn main() {
-    let ids = INPUT.lines().map(decode_boarding_id);
-
-    let result = match advent_of_code::part() {
-        advent_of_code::Part::One => ids.max().or_exit_with("empty input"),
-        advent_of_code::Part::Two => {
-            let mut ids: Vec<u16> = ids.collect();
-            ids.sort_unstable();
-            ids.windows(2)
-                .find_map(|pair| match pair {
-                    [a, b] if a + 1 != *b => Some(a + 1),
-                    _ => None,
-                })
-                .or_exit_with("no solution found")
-        }
-    };
-
-    println!("{}", result);
+use crate::prelude::*;
+use crate::Solution;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<u16>;
+    type Answer = u16;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        let mut ids = parser::parse(input, parser::lines(boarding_id))?;
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    fn part1(ids: &Self::Input) -> Result<Self::Answer, AocError> {
+        ids.last()
+            .copied()
+            .ok_or(AocError::NoSolution("there are no boarding passes"))
+    }
+
+    fn part2(ids: &Self::Input) -> Result<Self::Answer, AocError> {
+        ids.windows(2)
+            .find_map(|pair| match pair {
+                [a, b] if a + 1 != *b => Some(a + 1),
+                _ => None,
+            })
+            .ok_or(AocError::NoSolution("there is no free seat"))
+    }
+
+    /// A shuffled run of consecutive seats with exactly one free seat in between.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        let first = rng.range(8..=100);
+        let last = rng.range(first + 700..=first + 850);
+        let free = rng.range(first + 1..=last - 1);
+
+        let mut passes: Vec<String> = (first..=last)
+            .filter(|&id| id != free)
+            .map(|id| {
+                (0..10)
+                    .rev()
+                    .map(|bit| match (bit >= 3, id >> bit & 1 == 1) {
+                        (true, false) => 'F',
+                        (true, true) => 'B',
+                        (false, false) => 'L',
+                        (false, true) => 'R',
+                    })
+                    .chain(Some('\n'))
+                    .collect()
+            })
+            .collect();
+
+        rng.shuffle(&mut passes);
+        Some(passes.concat())
+    }
 }
 
-fn decode_boarding_id(code: &str) -> u16 {
-    let (row, col) = code.split_at(7);
+/// Seven `F`/`B` row bits followed by three `L`/`R` column bits.
+fn boarding_id(input: &str) -> PResult<'_, u16> {
+    let row_bit = parser::char_where(|c| c == 'F' || c == 'B', "'F' or 'B'");
+    let column_bit = parser::char_where(|c| c == 'L' || c == 'R', "'L' or 'R'");
 
-    let row = row.chars().fold(0, |a, c| match c {
-        'F' => a << 1,
-        'B' => (a << 1) | 1,
-        _ => panic!(),
-    });
+    let mut id = 0;
+    let mut rest = input;
 
-    let col = col.chars().fold(0, |a, c| match c {
-        'L' => a << 1,
-        'R' => (a << 1) | 1,
-        _ => panic!(),
-    });
+    for bit in 0..10 {
+        let (c, next) = match bit < 7 {
+            true => row_bit(rest)?,
+            false => column_bit(rest)?,
+        };
+        id = (id << 1) | matches!(c, 'B' | 'R') as u16;
+        rest = next;
+    }
 
-    (row << 3) | col
+    Ok((id, rest))
 }
+
+crate::examples!(Day05 {
+    part1: ("2020_05_1", One, 820),
+});
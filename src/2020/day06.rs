@@ -1,36 +1,95 @@
-const INPUT: &str = include_str!("../../input/2020_06.txt");
+use crate::prelude::*;
+use crate::Solution;
 
-fn main() {
-    let strategy = match advent_of_code::part() {
-        advent_of_code::Part::One => anyone_answered_yes,
-        advent_of_code::Part::Two => everyone_answered_yes,
-    };
+pub struct Day06;
 
-    let result: u32 = INPUT.split("\n\n").map(strategy).sum();
+impl Solution for Day06 {
+    type Input = Vec<Group>;
+    type Answer = u32;
 
-    println!("{}", result);
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parser::parse(input, parser::blocks(parser::lines(person)))?)
+    }
+
+    fn part1(groups: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(groups.iter().map(|g| anyone_answered_yes(g)).sum())
+    }
+
+    fn part2(groups: &Self::Input) -> Result<Self::Answer, AocError> {
+        Ok(groups.iter().map(|g| everyone_answered_yes(g)).sum())
+    }
+
+    /// About 500 groups of one to five people. Every group has a few questions its people are
+    /// likely to agree on, so part two doesn't come out as zero.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        let questions: Vec<char> = ('a'..='z').collect();
+        let mut groups = Vec::new();
+
+        for _ in 0..rng.range(450..=500) {
+            let common: Vec<char> = questions
+                .iter()
+                .filter(|_| rng.chance(0.2))
+                .copied()
+                .collect();
+            let mut group = String::new();
+
+            for _ in 0..rng.range(1..=5) {
+                let mut person: Vec<char> = questions
+                    .iter()
+                    .filter(|q| match common.contains(q) {
+                        true => rng.chance(0.9),
+                        false => rng.chance(0.1),
+                    })
+                    .copied()
+                    .collect();
+
+                if person.is_empty() {
+                    person.push(*rng.choose(&questions));
+                }
+                rng.shuffle(&mut person);
+
+                group.extend(person);
+                group.push('\n');
+            }
+
+            groups.push(group);
+        }
+
+        Some(groups.join("\n"))
+    }
 }
 
+/// Answers of every person in a group, one bitset of questions per person.
+pub type Group = Vec<u32>;
+
 const OFFSET: u32 = 'a' as u32;
 
-fn anyone_answered_yes(group: &str) -> u32 {
+/// Questions a person answered with yes as a bitset.
+fn person(input: &str) -> PResult<'_, u32> {
+    let question = parser::char_where(|c| c.is_ascii_lowercase(), "a question from 'a' to 'z'");
+
+    parser::map(parser::many1(question), |questions| {
+        questions
+            .into_iter()
+            .fold(0, |bitset, c| bitset | (1 << (c as u32 - OFFSET)))
+    })(input)
+}
+
+fn anyone_answered_yes(group: &[u32]) -> u32 {
     group
-        .chars()
-        .filter(|c| *c != '\n')
-        .fold(0u32, |bitset, c| bitset | ((1 << (c as u32)) - OFFSET))
+        .iter()
+        .fold(0, |set, answers| set | answers)
         .count_ones()
 }
 
-fn everyone_answered_yes(group: &str) -> u32 {
-    let mut set = u32::MAX;
-
-    for line in group.lines() {
-        let g = line
-            .chars()
-            .fold(0u32, |bitset, c| bitset | ((1 << (c as u32)) - OFFSET));
-
-        set &= g;
-    }
-
-    set.count_ones()
+fn everyone_answered_yes(group: &[u32]) -> u32 {
+    group
+        .iter()
+        .fold(u32::MAX, |set, answers| set & answers)
+        .count_ones()
 }
+
+crate::examples!(Day06 {
+    part1: ("2020_06_1", One, 11),
+    part2: ("2020_06_1", Two, 6),
+});
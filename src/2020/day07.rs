@@ -1,24 +1,100 @@
-use advent_of_code::prelude::*;
+use crate::prelude::*;
+use crate::Solution;
 
-const INPUT: &str = include_str!("../../input/2020_07.txt");
+pub struct Day07;
 
-fn main() {
-    let bags: BagRules = INPUT.parse().or_exit_with("can't parse input");
+impl Solution for Day07 {
+    type Input = BagRules;
+    type Answer = usize;
 
-    let gold_id = bags
-        .find_index("shiny gold")
-        .or_exit_with("there is no shiny gold bag");
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(input.parse()?)
+    }
 
-    let result = match advent_of_code::part() {
-        advent_of_code::Part::One => bags.containers(gold_id),
-        advent_of_code::Part::Two => bags.contained_bags(gold_id),
-    };
+    fn part1(bags: &Self::Input) -> Result<Self::Answer, AocError> {
+        let gold_id = bags.find_index("shiny gold").ok_or(NO_GOLD_BAG)?;
+        Ok(bags.containers(gold_id))
+    }
+
+    fn part2(bags: &Self::Input) -> Result<Self::Answer, AocError> {
+        let gold_id = bags.find_index("shiny gold").ok_or(NO_GOLD_BAG)?;
+        Ok(bags.contained_bags(gold_id))
+    }
+
+    /// Rules for about 400 bags spread over eight levels, where bags only contain bags of the
+    /// next two levels so the graph can't have cycles but is still deep. The shiny gold bag sits
+    /// on the fourth level to have both containers and contents.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        const ADJECTIVES: &[&str] = &[
+            "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light",
+            "mirrored", "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy",
+            "dusky", "wild",
+        ];
+        const COLORS: &[&str] = &[
+            "aqua", "beige", "black", "blue", "bronze", "brown", "coral", "crimson", "cyan",
+            "fuchsia", "gold", "gray", "green", "indigo", "lavender", "lime", "magenta", "maroon",
+            "olive", "orange", "plum", "purple", "red", "salmon", "silver", "tan", "teal", "white",
+        ];
+        const LEVELS: i64 = 8;
+        const GOLD_LEVEL: i64 = 3;
+
+        let mut bags: Vec<(String, i64)> = ADJECTIVES
+            .iter()
+            .flat_map(|adjective| {
+                COLORS
+                    .iter()
+                    .map(move |color| format!("{} {}", adjective, color))
+            })
+            .filter(|bag| bag != "shiny gold")
+            .map(|bag| (bag, rng.range(0..=LEVELS - 1)))
+            .collect();
+        rng.shuffle(&mut bags);
+        bags.truncate(rng.range(350..=450) as usize);
+        bags.push(("shiny gold".to_owned(), GOLD_LEVEL));
+
+        let mut rules = Vec::with_capacity(bags.len());
+
+        for (bag, level) in &bags {
+            let mut deeper: Vec<&str> = bags
+                .iter()
+                .filter(|(_, other)| (level + 1..=level + 2).contains(other))
+                .map(|(other, _)| other.as_str())
+                .collect();
+            rng.shuffle(&mut deeper);
+
+            // Favour the shiny gold bag so it ends up with a puzzle sized number of containers.
+            if let Some(gold) = deeper.iter().position(|&other| other == "shiny gold") {
+                if rng.chance(0.2) {
+                    deeper.swap(0, gold);
+                }
+            }
 
-    println!("{}", result);
+            let min = if bag == "shiny gold" { 1 } else { 0 };
+            let count = rng.range(min..=4).min(deeper.len() as i64) as usize;
+
+            let contents: Vec<String> = deeper[..count]
+                .iter()
+                .map(|child| match rng.range(1..=5) {
+                    1 => format!("1 {} bag", child),
+                    amount => format!("{} {} bags", amount, child),
+                })
+                .collect();
+
+            rules.push(match contents.is_empty() {
+                true => format!("{} bags contain no other bags.\n", bag),
+                false => format!("{} bags contain {}.\n", bag, contents.join(", ")),
+            });
+        }
+
+        rng.shuffle(&mut rules);
+        Some(rules.concat())
+    }
 }
 
+const NO_GOLD_BAG: AocError = AocError::NoSolution("there is no shiny gold bag");
+
 #[derive(Default, Debug)]
-struct BagRules {
+pub struct BagRules {
     bags: Vec<String>,
     rules: Vec<Rule>,
 }
@@ -85,33 +161,44 @@ struct Rule {
     amount: usize,
 }
 
+/// `<bag> bags contain <amount> <bag> bag(s), ... .` or `<bag> bags contain no other bags.`
+fn bag_rule(input: &str) -> PResult<'_, (&str, Vec<(usize, &str)>)> {
+    let bag = || {
+        parser::terminated(
+            parser::until(" bag"),
+            parser::pair(parser::literal(" bag"), parser::opt(parser::literal("s"))),
+        )
+    };
+    let content = parser::pair(
+        parser::terminated(parser::integer(), parser::literal(" ")),
+        bag(),
+    );
+    let contents = parser::or(
+        parser::map(parser::literal("no other bags"), |_| Vec::new()),
+        parser::separated(content, parser::literal(", ")),
+    );
+
+    parser::terminated(
+        parser::pair(
+            bag(),
+            parser::preceded(parser::literal(" contain "), contents),
+        ),
+        parser::literal("."),
+    )(input)
+}
+
 impl std::str::FromStr for BagRules {
-    type Err = InvalidFormat;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut bag_rules = BagRules::default();
 
-        for (ln, line) in s.lines().enumerate() {
-            let (container, rules) = split_once(line, " bags contain ")
-                .ok_or_else(|| InvalidFormat(line.to_owned(), ln))?;
-
+        for (container, contents) in parser::parse(s, parser::lines(bag_rule))? {
             let container = bag_rules.get_or_create(container);
 
-            if rules != "no other bags." {
-                for rule in rules.split(", ") {
-                    let (amount, bag) =
-                        split_once(rule, " ").ok_or_else(|| InvalidFormat(line.to_owned(), ln))?;
-
-                    let (bag, _) = split_once(bag, " bag")
-                        .ok_or_else(|| InvalidFormat(line.to_owned(), ln))?;
-
-                    let amount = amount
-                        .parse()
-                        .map_err(|_| InvalidFormat(line.to_owned(), ln))?;
-                    let bag = bag_rules.get_or_create(&bag);
-
-                    bag_rules.new_rule(container, bag, amount);
-                }
+            for (amount, bag) in contents {
+                let bag = bag_rules.get_or_create(bag);
+                bag_rules.new_rule(container, bag, amount);
             }
         }
 
@@ -119,13 +206,8 @@ impl std::str::FromStr for BagRules {
     }
 }
 
-#[derive(Debug)]
-struct InvalidFormat(String, usize);
-
-impl std::fmt::Display for InvalidFormat {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "invalid bag rule '{}' on line {}", self.0, self.1)
-    }
-}
-
-impl std::error::Error for InvalidFormat {}
+crate::examples!(Day07 {
+    part1: ("2020_07_1", One, 4),
+    part2: ("2020_07_1", Two, 32),
+    part2_nested: ("2020_07_2", Two, 126),
+});
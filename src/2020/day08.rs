@@ -1,38 +1,126 @@
-use advent_of_code::prelude::*;
+use crate::prelude::*;
+use crate::Solution;
 
-const INPUT: &str = include_str!("../../input/2020_08.txt");
+pub struct Day08;
 
-fn main() {
-    let program = INPUT
-        .lines()
-        .enumerate()
-        .map(|(ln, line)| {
-            line.parse()
-                .or_exit_with(format!("couldn't parse instruction on line '{}'", ln + 1))
-        })
-        .collect();
+impl Solution for Day08 {
+    type Input = Program;
+    type Answer = i32;
 
-    let solution = match advent_of_code::part() {
-        advent_of_code::Part::One => {
-            let mut computer = Computer::default();
-            let (result, _) = computer.run(&program);
-            result
-        }
-        advent_of_code::Part::Two => program
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(Program(parser::parse(input, parser::lines(instruction))?))
+    }
+
+    fn part1(program: &Self::Input) -> Result<Self::Answer, AocError> {
+        let mut computer = Computer::default();
+        let (result, _) = computer.run(program);
+        Ok(result)
+    }
+
+    fn part2(program: &Self::Input) -> Result<Self::Answer, AocError> {
+        program
             .patches()
             .find_map(|patch| {
                 let mut computer = Computer::with_patch(patch);
-                match computer.run(&program) {
+                match computer.run(program) {
                     (result, true) => Some(result),
                     _ => None,
                 }
             })
-            .or_exit_with("didn't find correct patch"),
+            .ok_or(AocError::NoSolution("no patch makes the program terminate"))
+    }
+
+    fn alternatives() -> Vec<Alternative<Self>> {
+        vec![Alternative {
+            name: "terminating set",
+            part: Part::Two,
+            solve: patch_on_path,
+        }]
+    }
+
+    /// Program of a few hundred instructions that loops, with exactly one patch making it
+    /// terminate.
+    fn generate(rng: &mut Rng) -> Option<String> {
+        loop {
+            let len = rng.range(100..=300);
+            let instructions: Vec<_> = (0..len)
+                .map(|_| {
+                    let offset = match rng.range(-len / 4..=len / 4) {
+                        0 => 1,
+                        offset => offset as i32,
+                    };
+                    match rng.range(0..=5) {
+                        0..=2 => Acc(rng.range(-50..=50) as i32),
+                        3 | 4 => Jump(offset),
+                        _ => NoOp(offset),
+                    }
+                })
+                .collect();
+            let program = Program(instructions);
+
+            let terminating = program
+                .patches()
+                .map(|patch| Computer::with_patch(patch).run(&program).1)
+                .filter(|&terminates| terminates)
+                .count();
+
+            if !Computer::default().run(&program).1 && terminating == 1 {
+                return Some(program.to_string());
+            }
+        }
+    }
+}
+
+/// Follows the unpatched program and patches the first instruction whose patched successor
+/// reaches the end, found by walking the graph of successors backwards from the end.
+fn patch_on_path(program: &Program) -> Result<i32, AocError> {
+    let len = program.0.len() as i32;
+    let successor = |index: i32, instruction: &Instruction| match instruction {
+        Jump(offset) => index + offset,
+        _ => index + 1,
     };
 
-    println!("{}", solution);
+    // Leaving the program in either direction ends up in the extra node at `len`.
+    let node = |next: i32| match (0..len).contains(&next) {
+        true => next as usize,
+        false => len as usize,
+    };
+
+    let mut predecessors = vec![Vec::new(); program.0.len() + 1];
+    for (index, instruction) in program.0.iter().enumerate() {
+        predecessors[node(successor(index as i32, instruction))].push(index);
+    }
+
+    // Instructions from which the unpatched program terminates.
+    let mut terminates = vec![false; program.0.len() + 1];
+    let mut stack = vec![program.0.len()];
+    while let Some(index) = stack.pop() {
+        if !std::mem::replace(&mut terminates[index], true) {
+            stack.extend(&predecessors[index]);
+        }
+    }
+
+    let mut index = 0;
+    let mut visited = vec![false; program.0.len()];
+    while let Some(instruction) = program.0.get(index as usize) {
+        if std::mem::replace(&mut visited[index as usize], true) {
+            break;
+        }
+
+        if let Some(patch) = Patch::for_instruction(instruction, index as usize) {
+            if terminates[node(successor(index, &patch.patched_instruction))] {
+                let mut computer = Computer::with_patch(patch);
+                return Ok(computer.run(program).0);
+            }
+        }
+
+        index = successor(index, instruction);
+    }
+
+    Err(AocError::NoSolution("no patch makes the program terminate"))
 }
-struct Program(Vec<Instruction>);
+
+pub struct Program(Vec<Instruction>);
 
 struct Patch {
     index: usize,
@@ -114,33 +202,58 @@ impl Computer {
     }
 }
 
+impl std::fmt::Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for instruction in &self.0 {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
-enum Instruction {
+pub enum Instruction {
     Acc(i32),
     Jump(i32),
     NoOp(i32),
 }
 use Instruction::*;
 
-impl std::str::FromStr for Instruction {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (opcode, param) = split_once(s, " ").ok_or("invalid instruction format")?;
-        let param = param.parse().map_err(|_| "invalid parameter")?;
-
-        match opcode {
-            "nop" => Ok(NoOp(param)),
-            "jmp" => Ok(Jump(param)),
-            "acc" => Ok(Acc(param)),
-            _ => Err("unknown instruction"),
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Acc(n) => write!(f, "acc {:+}", n),
+            Jump(n) => write!(f, "jmp {:+}", n),
+            NoOp(n) => write!(f, "nop {:+}", n),
         }
     }
 }
 
-impl std::iter::FromIterator<Instruction> for Program {
-    fn from_iter<I: IntoIterator<Item = Instruction>>(iter: I) -> Self {
-        let instructions = iter.into_iter().collect();
-        Program(instructions)
+/// `<opcode> <parameter>` with a signed parameter.
+fn instruction(input: &str) -> PResult<'_, Instruction> {
+    let opcode = parser::map_res(
+        parser::take_while1(char::is_alphabetic, "an opcode"),
+        |opcode| match opcode {
+            "nop" => Ok(NoOp as fn(i32) -> Instruction),
+            "jmp" => Ok(Jump),
+            "acc" => Ok(Acc),
+            _ => Err(format!("unknown instruction '{}'", opcode)),
+        },
+    );
+    let param = parser::preceded(parser::literal(" "), parser::integer());
+
+    parser::map(parser::pair(opcode, param), |(opcode, param)| opcode(param))(input)
+}
+
+impl std::str::FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse(s, instruction)
     }
 }
+
+crate::examples!(Day08 {
+    part1: ("2020_08_1", One, 5),
+    part2: ("2020_08_1", Two, 8),
+});
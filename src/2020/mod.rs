@@ -0,0 +1,53 @@
+use crate::Day;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+
+pub const DAYS: &[Day] = &[
+    Day {
+        year: 2020,
+        day: 1,
+        solution: &day01::Day01,
+    },
+    Day {
+        year: 2020,
+        day: 2,
+        solution: &day02::Day02,
+    },
+    Day {
+        year: 2020,
+        day: 3,
+        solution: &day03::Day03,
+    },
+    Day {
+        year: 2020,
+        day: 4,
+        solution: &day04::Day04,
+    },
+    Day {
+        year: 2020,
+        day: 5,
+        solution: &day05::Day05,
+    },
+    Day {
+        year: 2020,
+        day: 6,
+        solution: &day06::Day06,
+    },
+    Day {
+        year: 2020,
+        day: 7,
+        solution: &day07::Day07,
+    },
+    Day {
+        year: 2020,
+        day: 8,
+        solution: &day08::Day08,
+    },
+];
@@ -0,0 +1,132 @@
+use crate::diagnostic::ParseError;
+use crate::{AocError, Part};
+use std::path::PathBuf;
+
+/// Answers recorded for a day in `answers/<year>_<day>.txt`, one `part<n>: <answer>` line per part.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+impl Answers {
+    pub fn path(year: u16, day: u8) -> PathBuf {
+        PathBuf::from(format!("answers/{}_{:02}.txt", year, day))
+    }
+
+    /// Days without an answers file have no recorded answers.
+    pub fn load(year: u16, day: u8) -> Result<Answers, AocError> {
+        let path = Answers::path(year, day);
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(content.parse()?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(AocError::io(
+                format!("couldn't read {}", path.display()),
+                err,
+            )),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    /// `None` means the part failed, which is an error whatever answer is recorded.
+    pub fn check(&self, part: Part, answer: Option<&str>) -> Verdict {
+        match (self.get(part), answer) {
+            (_, None) => Verdict::Error,
+            (None, _) => Verdict::Unknown,
+            (Some(expected), Some(actual)) if expected == actual => Verdict::Pass,
+            (Some(expected), Some(_)) => Verdict::Fail {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+
+        for line in s.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (key, answer) = crate::prelude::split_once(line, ":")
+                .ok_or_else(|| ParseError::at(s, line, "expected 'part<n>: <answer>'"))?;
+
+            let slot = match key.trim() {
+                "part1" => &mut answers.part1,
+                "part2" => &mut answers.part2,
+                _ => return Err(ParseError::at(s, key, format!("unknown part '{}'", key))),
+            };
+
+            *slot = Some(answer.trim().to_owned());
+        }
+
+        Ok(answers)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// The part didn't produce an answer.
+    Error,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error)
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Error => write!(f, "ERROR"),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check() {
+        let answers: Answers = "part1: 42\n".parse().unwrap();
+
+        assert_eq!(answers.check(Part::One, Some("42")), Verdict::Pass);
+        assert_eq!(
+            answers.check(Part::One, Some("41")),
+            Verdict::Fail {
+                expected: "42".to_owned()
+            }
+        );
+        assert_eq!(answers.check(Part::Two, Some("42")), Verdict::Unknown);
+        assert_eq!(answers.check(Part::One, None), Verdict::Error);
+        assert_eq!(answers.check(Part::Two, None), Verdict::Error);
+        assert!(answers.check(Part::Two, None).is_failure());
+    }
+
+    #[test]
+    fn rejects_unknown_parts() {
+        assert!("part3: 1".parse::<Answers>().is_err());
+        assert!("42".parse::<Answers>().is_err());
+    }
+}
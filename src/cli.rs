@@ -0,0 +1,584 @@
+use crate::input::InputSource;
+use crate::y2020::day03::Edge;
+use crate::Part;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: aoc <year> <day> [options]
+       aoc check-all
+       aoc run-all [--threads <n>]
+       aoc status
+       aoc watch <year> <day>
+       aoc new <year> <day>
+       aoc generate <year> <day> [--seed <n>]
+       aoc slopes [--max <n>] [--top <k>] [--input <file>|-]
+       aoc draw-slopes [<right>,<down>...] [--edge <edge>] [--ppm <file>] [--scale <n>]
+                       [--input <file>|-]
+       aoc submit <year> <day> 1|2
+
+options:
+    --part 1|2|both     which parts to run (default: both)
+    --input <file>|-    read input from a file or stdin (default: input/<year>_<day>.txt,
+                        downloaded when missing using the session token in AOC_SESSION or aoc.conf)
+    --time              report how long parsing and each part took
+    --bench <n>         repeat parsing and each part, including its alternative implementations,
+                        n times and report timing statistics
+    --check             compare answers with the ones recorded in answers/<year>_<day>.txt,
+                        which are for the default input
+    --cross-check       compare all implementations of each part on the input and on generated inputs
+    --output text|json  print answers as text or as one JSON object per part (default: text)
+    -h, --help          print this message
+
+commands:
+    check-all           check every registered day against its recorded answers
+    run-all             run every registered day in parallel and print a summary table,
+                        using n threads (default: the number of CPUs)
+    status              show a calendar of solved days, recorded answers and examples
+    watch <year> <day>  re-run a day on its input and examples whenever one of them changes
+    new <year> <day>    create and register a solution template, input and example file
+    generate <year> <day>
+                        print a random input for a day, reproducible with --seed n (default: 1)
+    submit <year> <day> 1|2
+                        submit the answer of a part, unless it was submitted before
+    slopes              list the k slopes (default: 5) with the fewest and the most trees on the
+                        toboggan map of 2020 day 3, trying every direction up to n (default: 10)
+    draw-slopes         draw slopes (default: the five of part two) on the toboggan map in the
+                        terminal, or as a PPM image with n pixels per tile (default: 4); the edge
+                        is wrap-x (default), wrap-both, stop or bounce";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunOptions),
+    CheckAll,
+    Status,
+    RunAll {
+        threads: Option<usize>,
+    },
+    Watch {
+        year: u16,
+        day: u8,
+    },
+    New {
+        year: u16,
+        day: u8,
+    },
+    Generate {
+        year: u16,
+        day: u8,
+        seed: u64,
+    },
+    Slopes {
+        input: InputSource,
+        max: i64,
+        top: usize,
+    },
+    DrawSlopes(DrawOptions),
+    Submit {
+        year: u16,
+        day: u8,
+        part: Part,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunOptions {
+    pub year: u16,
+    pub day: u8,
+    pub parts: Vec<Part>,
+    pub input: InputSource,
+    pub time: bool,
+    pub bench: Option<usize>,
+    pub check: bool,
+    pub cross_check: bool,
+    pub output: Output,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DrawOptions {
+    pub input: InputSource,
+    /// `(right, down)` of every slope.
+    pub slopes: Vec<(i64, i64)>,
+    pub edge: Edge,
+    /// Where to write a PPM image instead of drawing in the terminal.
+    pub ppm: Option<PathBuf>,
+    pub scale: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    Text,
+    /// One JSON object per line and part.
+    Json,
+}
+
+impl Command {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, UsageError> {
+        let mut args = Args(args.into_iter());
+
+        match args.next() {
+            Some(Arg::Positional(command)) if command == "check-all" => {
+                args.finish().map(|_| Command::CheckAll)
+            }
+            Some(Arg::Positional(command)) if command == "status" => {
+                args.finish().map(|_| Command::Status)
+            }
+            Some(Arg::Positional(command)) if command == "run-all" => {
+                let mut threads = None;
+
+                while let Some(arg) = args.next() {
+                    match arg {
+                        Arg::Flag(flag, value) if flag == "--threads" => {
+                            threads = Some(parse_threads(&args.value(&flag, value)?)?)
+                        }
+                        Arg::Flag(flag, _) => {
+                            return Err(UsageError(format!("unknown flag '{}'", flag)))
+                        }
+                        Arg::Positional(value) => {
+                            return Err(UsageError(format!("unexpected argument '{}'", value)))
+                        }
+                    }
+                }
+
+                Ok(Command::RunAll { threads })
+            }
+            Some(Arg::Positional(command)) if command == "watch" || command == "new" => {
+                let (year, day) = match (args.next(), args.next()) {
+                    (Some(Arg::Positional(year)), Some(Arg::Positional(day))) => (year, day),
+                    _ => return Err(UsageError("expected <year> and <day>".to_owned())),
+                };
+                args.finish()?;
+
+                let (year, day) = (parse_year(&year)?, parse_day(&day)?);
+                match command.as_str() {
+                    "watch" => Ok(Command::Watch { year, day }),
+                    _ => Ok(Command::New { year, day }),
+                }
+            }
+            Some(Arg::Positional(command)) if command == "generate" => {
+                let mut positional = Vec::new();
+                let mut seed = 1;
+
+                while let Some(arg) = args.next() {
+                    match arg {
+                        Arg::Flag(flag, value) if flag == "--seed" => {
+                            seed = parse_seed(&args.value(&flag, value)?)?
+                        }
+                        Arg::Flag(flag, _) => {
+                            return Err(UsageError(format!("unknown flag '{}'", flag)))
+                        }
+                        Arg::Positional(value) => positional.push(value),
+                    }
+                }
+
+                match positional.as_slice() {
+                    [year, day] => Ok(Command::Generate {
+                        year: parse_year(year)?,
+                        day: parse_day(day)?,
+                        seed,
+                    }),
+                    _ => Err(UsageError("expected <year> and <day>".to_owned())),
+                }
+            }
+            Some(Arg::Positional(command)) if command == "slopes" => {
+                let mut input = InputSource::Default;
+                let mut max = 10;
+                let mut top = 5;
+
+                while let Some(arg) = args.next() {
+                    match arg {
+                        Arg::Flag(flag, value) => match flag.as_str() {
+                            "--input" => input = InputSource::from_arg(&args.value(&flag, value)?),
+                            "--max" => max = parse_count(&flag, &args.value(&flag, value)?)? as i64,
+                            "--top" => top = parse_count(&flag, &args.value(&flag, value)?)?,
+                            _ => return Err(UsageError(format!("unknown flag '{}'", flag))),
+                        },
+                        Arg::Positional(value) => {
+                            return Err(UsageError(format!("unexpected argument '{}'", value)))
+                        }
+                    }
+                }
+
+                Ok(Command::Slopes { input, max, top })
+            }
+            Some(Arg::Positional(command)) if command == "draw-slopes" => parse_draw(args),
+            Some(Arg::Positional(command)) if command == "submit" => {
+                let (year, day, part) = match (args.next(), args.next(), args.next()) {
+                    (
+                        Some(Arg::Positional(year)),
+                        Some(Arg::Positional(day)),
+                        Some(Arg::Positional(part)),
+                    ) => (year, day, part),
+                    _ => return Err(UsageError("expected <year>, <day> and <part>".to_owned())),
+                };
+                args.finish()?;
+
+                let part = match parse_parts(&part)?.as_slice() {
+                    [part] => *part,
+                    _ => return Err(UsageError("only one part can be submitted".to_owned())),
+                };
+
+                Ok(Command::Submit {
+                    year: parse_year(&year)?,
+                    day: parse_day(&day)?,
+                    part,
+                })
+            }
+            Some(Arg::Flag(flag, _)) if flag == "-h" || flag == "--help" => Ok(Command::Help),
+            Some(Arg::Positional(year)) => parse_run(year, args),
+            _ => Err(UsageError("expected <year> and <day>".to_owned())),
+        }
+    }
+}
+
+fn parse_run(
+    year: String,
+    mut args: Args<impl Iterator<Item = String>>,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut parts = vec![Part::One, Part::Two];
+    let mut input = InputSource::Default;
+    let mut time = false;
+    let mut bench = None;
+    let mut check = false;
+    let mut cross_check = false;
+    let mut output = Output::Text;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Positional(value) if day.is_none() => day = Some(value),
+            Arg::Positional(value) => {
+                return Err(UsageError(format!("unexpected argument '{}'", value)))
+            }
+            Arg::Flag(flag, value) => match flag.as_str() {
+                "-h" | "--help" => return Ok(Command::Help),
+                "--part" => parts = parse_parts(&args.value(&flag, value)?)?,
+                "--input" => input = InputSource::from_arg(&args.value(&flag, value)?),
+                "--time" => time = true,
+                "--bench" => bench = Some(parse_iterations(&args.value(&flag, value)?)?),
+                "--check" => check = true,
+                "--cross-check" => cross_check = true,
+                "--output" => output = parse_output(&args.value(&flag, value)?)?,
+                _ => return Err(UsageError(format!("unknown flag '{}'", flag))),
+            },
+        }
+    }
+
+    let day = day.ok_or_else(|| UsageError("expected <year> and <day>".to_owned()))?;
+
+    // The recorded answers are the ones for the default input.
+    if check && input != InputSource::Default {
+        return Err(UsageError(
+            "--check can't be combined with --input".to_owned(),
+        ));
+    }
+
+    // Cross-checking only prints whether the implementations agree.
+    let conflicts = [
+        ("--output json", output == Output::Json),
+        ("--bench", bench.is_some()),
+        ("--check", check),
+        ("--time", time),
+    ];
+    let conflict = conflicts.iter().find(|&&(_, set)| set);
+    if let (true, Some((flag, _))) = (cross_check, conflict) {
+        return Err(UsageError(format!(
+            "--cross-check can't be combined with {}",
+            flag
+        )));
+    }
+
+    if bench.is_some() && output == Output::Json {
+        return Err(UsageError(
+            "--bench can't be combined with --output json".to_owned(),
+        ));
+    }
+
+    Ok(Command::Run(RunOptions {
+        year: parse_year(&year)?,
+        day: parse_day(&day)?,
+        parts,
+        input,
+        time,
+        bench,
+        check,
+        cross_check,
+        output,
+    }))
+}
+
+fn parse_draw(mut args: Args<impl Iterator<Item = String>>) -> Result<Command, UsageError> {
+    let mut options = DrawOptions {
+        input: InputSource::Default,
+        slopes: Vec::new(),
+        edge: Edge::WrapX,
+        ppm: None,
+        scale: 4,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Positional(slope) => options.slopes.push(parse_slope(&slope)?),
+            Arg::Flag(flag, value) => match flag.as_str() {
+                "--input" => options.input = InputSource::from_arg(&args.value(&flag, value)?),
+                "--edge" => options.edge = args.value(&flag, value)?.parse().map_err(UsageError)?,
+                "--ppm" => options.ppm = Some(PathBuf::from(args.value(&flag, value)?)),
+                "--scale" => options.scale = parse_count(&flag, &args.value(&flag, value)?)?,
+                _ => return Err(UsageError(format!("unknown flag '{}'", flag))),
+            },
+        }
+    }
+
+    if options.slopes.is_empty() {
+        options.slopes = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+    }
+
+    Ok(Command::DrawSlopes(options))
+}
+
+/// `<right>,<down>`
+fn parse_slope(value: &str) -> Result<(i64, i64), UsageError> {
+    let slope = crate::prelude::split_once(value, ",")
+        .and_then(|(right, down)| Some((right.parse().ok()?, down.parse().ok()?)));
+
+    slope.ok_or_else(|| UsageError(format!("invalid slope '{}'", value)))
+}
+
+enum Arg {
+    /// A flag together with its value when written as `--flag=value`.
+    Flag(String, Option<String>),
+    Positional(String),
+}
+
+struct Args<I>(I);
+
+impl<I: Iterator<Item = String>> Args<I> {
+    fn next(&mut self) -> Option<Arg> {
+        let arg = self.0.next()?;
+
+        // `-` is stdin and `-1,2` a slope going left, neither is a flag.
+        let negative = arg
+            .strip_prefix('-')
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+        if !arg.starts_with('-') || arg == "-" || negative {
+            return Some(Arg::Positional(arg));
+        }
+
+        match crate::prelude::split_once(&arg, "=") {
+            Some((flag, value)) if flag.starts_with("--") => {
+                Some(Arg::Flag(flag.to_owned(), Some(value.to_owned())))
+            }
+            _ => Some(Arg::Flag(arg, None)),
+        }
+    }
+
+    /// Value of a flag, either inline or the following argument.
+    fn value(&mut self, flag: &str, inline: Option<String>) -> Result<String, UsageError> {
+        inline
+            .or_else(|| self.0.next())
+            .ok_or_else(|| UsageError(format!("{} requires a value", flag)))
+    }
+
+    fn finish(mut self) -> Result<(), UsageError> {
+        match self.0.next() {
+            Some(arg) => Err(UsageError(format!("unexpected argument '{}'", arg))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_year(value: &str) -> Result<u16, UsageError> {
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("invalid year '{}'", value)))
+}
+
+fn parse_day(value: &str) -> Result<u8, UsageError> {
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("invalid day '{}'", value)))
+}
+
+fn parse_parts(value: &str) -> Result<Vec<Part>, UsageError> {
+    match value {
+        "1" => Ok(vec![Part::One]),
+        "2" => Ok(vec![Part::Two]),
+        "both" => Ok(vec![Part::One, Part::Two]),
+        _ => Err(UsageError(format!("invalid part '{}'", value))),
+    }
+}
+
+fn parse_output(value: &str) -> Result<Output, UsageError> {
+    match value {
+        "text" => Ok(Output::Text),
+        "json" => Ok(Output::Json),
+        _ => Err(UsageError(format!("invalid output format '{}'", value))),
+    }
+}
+
+fn parse_threads(value: &str) -> Result<usize, UsageError> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(UsageError(format!("invalid number of threads '{}'", value))),
+    }
+}
+
+fn parse_count(flag: &str, value: &str) -> Result<usize, UsageError> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(UsageError(format!(
+            "invalid value '{}' for {}",
+            value, flag
+        ))),
+    }
+}
+
+fn parse_seed(value: &str) -> Result<u64, UsageError> {
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("invalid seed '{}'", value)))
+}
+
+fn parse_iterations(value: &str) -> Result<usize, UsageError> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(UsageError(format!(
+            "invalid number of iterations '{}'",
+            value
+        ))),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n\n{}", self.0, USAGE)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, UsageError> {
+        Command::parse(args.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn defaults_to_both_parts() {
+        assert_eq!(
+            parse("2020 3"),
+            Ok(Command::Run(RunOptions {
+                year: 2020,
+                day: 3,
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Default,
+                time: false,
+                bench: None,
+                check: false,
+                cross_check: false,
+                output: Output::Text,
+            }))
+        );
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(
+            parse("2020 --part 2 8 --input=- --bench 10"),
+            Ok(Command::Run(RunOptions {
+                year: 2020,
+                day: 8,
+                parts: vec![Part::Two],
+                input: InputSource::Stdin,
+                time: false,
+                bench: Some(10),
+                check: false,
+                cross_check: false,
+                output: Output::Text,
+            }))
+        );
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse("check-all"), Ok(Command::CheckAll));
+        assert!(parse("check-all 2020").is_err());
+        assert_eq!(parse("status"), Ok(Command::Status));
+        assert_eq!(
+            parse("run-all --threads=3"),
+            Ok(Command::RunAll { threads: Some(3) })
+        );
+        assert!(parse("run-all --threads 0").is_err());
+        assert_eq!(parse("new 2020 9"), Ok(Command::New { year: 2020, day: 9 }));
+        assert!(parse("new 2020").is_err());
+        assert_eq!(
+            parse("watch 2020 3"),
+            Ok(Command::Watch { year: 2020, day: 3 })
+        );
+        assert_eq!(
+            parse("submit 2020 9 2"),
+            Ok(Command::Submit {
+                year: 2020,
+                day: 9,
+                part: Part::Two
+            })
+        );
+        assert!(parse("submit 2020 9 both").is_err());
+        assert_eq!(
+            parse("generate 2020 --seed=7 4"),
+            Ok(Command::Generate {
+                year: 2020,
+                day: 4,
+                seed: 7
+            })
+        );
+        assert!(parse("generate 2020 4 --seed x").is_err());
+        assert!(parse("generate 2020").is_err());
+        assert_eq!(
+            parse("slopes --top 3"),
+            Ok(Command::Slopes {
+                input: InputSource::Default,
+                max: 10,
+                top: 3
+            })
+        );
+        assert!(parse("slopes --max 0").is_err());
+        assert_eq!(
+            parse("draw-slopes 3,1 -1,2 --edge bounce --ppm out.ppm"),
+            Ok(Command::DrawSlopes(DrawOptions {
+                input: InputSource::Default,
+                slopes: vec![(3, 1), (-1, 2)],
+                edge: Edge::Bounce,
+                ppm: Some(PathBuf::from("out.ppm")),
+                scale: 4,
+            }))
+        );
+        assert!(parse("draw-slopes 3").is_err());
+        assert!(parse("draw-slopes --edge sideways").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        assert!(parse("2020 8 --part2").is_err());
+        assert!(parse("2020 8 --part 3").is_err());
+        assert!(parse("2020 8 --part").is_err());
+        assert!(parse("2020").is_err());
+        assert!(parse("2020 8 --bench 0").is_err());
+        assert!(parse("2020 8 --output xml").is_err());
+        assert!(parse("2020 8 --bench 3 --output json").is_err());
+        assert!(parse("2020 8 --check --input examples/2020_08_1.txt").is_err());
+        assert!(parse("2020 3 --cross-check --output json").is_err());
+        assert!(parse("2020 3 --cross-check --bench 3").is_err());
+        assert!(parse("2020 3 --cross-check --check").is_err());
+        assert!(parse("2020 3 --cross-check --time").is_err());
+        assert!(parse("2020 3 --cross-check --part 1").is_ok());
+        assert!(Command::parse(vec![String::new()]).is_err());
+        assert!(parse("é").is_err());
+        assert!(parse("2020 8 -é").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::diagnostic::ParseError;
+use crate::AocError;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Settings for talking to the puzzle server, read from `aoc.conf` (or the file in `AOC_CONFIG`)
+/// with one `<key>: <value>` line per setting. The `AOC_SESSION` and `AOC_BASE_URL` environment
+/// variables take precedence over the file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Value of the `session` cookie of a logged in user.
+    pub session: Option<String>,
+    pub base_url: String,
+    /// Minimal time between two requests to the server.
+    pub request_interval: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            request_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        std::env::var_os("AOC_CONFIG").map_or_else(|| PathBuf::from("aoc.conf"), PathBuf::from)
+    }
+
+    /// A missing config file means the defaults.
+    pub fn load() -> Result<Config, AocError> {
+        let path = Config::path();
+
+        let mut config: Config = match std::fs::read_to_string(&path) {
+            Ok(content) => content.parse()?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            Err(err) => {
+                return Err(AocError::io(
+                    format!("couldn't read {}", path.display()),
+                    err,
+                ))
+            }
+        };
+
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = std::env::var("AOC_BASE_URL") {
+            config.base_url = base_url.trim_end_matches('/').to_owned();
+        }
+
+        Ok(config)
+    }
+
+    pub fn session(&self) -> Result<&str, AocError> {
+        self.session.as_deref().ok_or_else(|| {
+            AocError::Config(format!(
+                "no session token, set AOC_SESSION or add 'session: <token>' to {}",
+                Config::path().display()
+            ))
+        })
+    }
+}
+
+impl std::str::FromStr for Config {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = crate::prelude::split_once(line, ":")
+                .ok_or_else(|| ParseError::at(s, line, "expected '<key>: <value>'"))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "session" => config.session = Some(value.to_owned()),
+                "base-url" => config.base_url = value.trim_end_matches('/').to_owned(),
+                "request-interval" => {
+                    let seconds = value
+                        .parse()
+                        .map_err(|_| ParseError::at(s, value, "expected a number of seconds"))?;
+                    config.request_interval = Duration::from_secs(seconds);
+                }
+                _ => return Err(ParseError::at(s, line, "unknown setting")),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let config: Config =
+            "session: abc\nbase-url: http://localhost:8080/\nrequest-interval: 0\n"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                session: Some("abc".to_owned()),
+                base_url: "http://localhost:8080".to_owned(),
+                request_interval: Duration::from_secs(0),
+            }
+        );
+        assert!("token: abc".parse::<Config>().is_err());
+    }
+}
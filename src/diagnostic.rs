@@ -0,0 +1,94 @@
+/// Parse error pointing at a position in the input, rendered together with the offending line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset into the input.
+    pub offset: usize,
+    /// Line of the error, starting at 1.
+    pub line: usize,
+    /// Column of the error in characters, starting at 1.
+    pub column: usize,
+    pub message: String,
+    snippet: String,
+}
+
+impl ParseError {
+    pub fn new(input: &str, offset: usize, message: impl Into<String>) -> ParseError {
+        let offset = offset.min(input.len());
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        ParseError {
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            message: message.into(),
+            snippet: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_owned(),
+        }
+    }
+
+    /// Error at the start of `part`, which has to be a slice of `input`.
+    pub fn at(input: &str, part: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(input, offset_in(input, part), message)
+    }
+
+    /// Moves an error found while parsing `part` to its position in the whole `input`.
+    ///
+    /// Panics when `part` isn't a slice of `input`.
+    pub fn within(self, input: &str, part: &str) -> ParseError {
+        ParseError::new(input, offset_in(input, part) + self.offset, self.message)
+    }
+}
+
+fn offset_in(input: &str, part: &str) -> usize {
+    (part.as_ptr() as usize)
+        .checked_sub(input.as_ptr() as usize)
+        .filter(|offset| offset + part.len() <= input.len())
+        .expect("part has to be a slice of the input")
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = self.line.to_string().len();
+
+        writeln!(f, "{}:{}: {}", self.line, self.column, self.message)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(
+            f,
+            "{:gutter$} | {:>column$}",
+            "",
+            "^",
+            gutter = gutter,
+            column = self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position() {
+        let input = "abc\ndef\nghi";
+        let err = ParseError::new(input, 5, "unexpected 'e'");
+
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.to_string(), "2:2: unexpected 'e'\n2 | def\n  |  ^");
+    }
+
+    #[test]
+    fn within() {
+        let input = "abc\ndef\nghi";
+        let line = input.lines().nth(2).unwrap();
+        let err = ParseError::new(line, 1, "unexpected 'h'").within(input, line);
+
+        assert_eq!((err.offset, err.line, err.column), (9, 3, 2));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::cli::UsageError;
+use crate::diagnostic::ParseError;
+
+/// Every way running a day can fail, each with its own exit code.
+#[derive(Debug)]
+pub enum AocError {
+    /// Answers didn't match the recorded ones, exits with 1.
+    Regression(usize),
+    /// Invalid command line, exits with 2.
+    Usage(UsageError),
+    /// Reading input or other files failed, exits with 3.
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+    /// Input doesn't have the expected format, exits with 4.
+    Parse(ParseError),
+    /// The input was parsed but there is no answer for it, exits with 5.
+    NoSolution(&'static str),
+    /// A setting needed to talk to the puzzle server is missing, exits with 6.
+    Config(String),
+}
+
+impl AocError {
+    pub fn io(context: impl Into<String>, source: std::io::Error) -> AocError {
+        AocError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AocError::Regression(_) => 1,
+            AocError::Usage(_) => 2,
+            AocError::Io { .. } => 3,
+            AocError::Parse(_) => 4,
+            AocError::NoSolution(_) => 5,
+            AocError::Config(_) => 6,
+        }
+    }
+}
+
+impl std::fmt::Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Regression(count) => write!(f, "{} regression(s) found", count),
+            AocError::Usage(err) => write!(f, "{}", err),
+            AocError::Io { context, source } => write!(f, "{}: {}", context, source),
+            AocError::Parse(err) => write!(f, "parse error at {}", err),
+            AocError::NoSolution(reason) => write!(f, "no solution found: {}", reason),
+            AocError::Config(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            AocError::Usage(err) => Some(err),
+            AocError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(err: ParseError) -> Self {
+        AocError::Parse(err)
+    }
+}
+
+impl From<UsageError> for AocError {
+    fn from(err: UsageError) -> Self {
+        AocError::Usage(err)
+    }
+}
@@ -0,0 +1,323 @@
+//! Rectangular maps of tiles, stored row by row.
+//!
+//! Positions are `(x, y)` pairs with `x` growing to the right and `y` growing downwards, so
+//! `(0, 0)` is the top left tile of the input.
+
+use crate::diagnostic::ParseError;
+use crate::parser;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    tiles: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+/// How [`Grid::resolve`] treats positions outside of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    /// Positions outside of the grid don't exist.
+    None,
+    /// The grid repeats to the left and right.
+    X,
+    /// The grid repeats above and below.
+    Y,
+    /// The grid repeats in every direction.
+    Both,
+}
+
+const NEIGHBORS4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBORS8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+impl<T> Grid<T> {
+    /// Panics when there aren't `width * height` tiles.
+    pub fn new(width: usize, height: usize, tiles: Vec<T>) -> Grid<T> {
+        assert_eq!(tiles.len(), width * height, "wrong number of tiles");
+        Grid {
+            tiles,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, f: impl FnMut((usize, usize)) -> T) -> Grid<T> {
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(f)
+            .collect();
+        Grid::new(width, height, tiles)
+    }
+
+    /// Parses one row per line, mapping every character to a tile with `tile`. Characters it
+    /// doesn't map are reported as not being `expected`.
+    pub fn parse(
+        input: &str,
+        tile: impl Fn(char) -> Option<T>,
+        expected: &'static str,
+    ) -> Result<Grid<T>, ParseError> {
+        let tile = parser::map_res(parser::char_where(|_| true, expected), |c| {
+            tile(c).ok_or_else(|| format!("expected {}", expected))
+        });
+        let rows = parser::parse(input, parser::lines(parser::many1(tile)))?;
+
+        let width = match rows.first() {
+            Some(row) => row.len(),
+            None => return Err(ParseError::new(input, 0, "map is empty")),
+        };
+        let height = rows.len();
+
+        let mut tiles = Vec::with_capacity(width * height);
+
+        for (row, line) in rows.into_iter().zip(input.lines()) {
+            if row.len() != width {
+                return Err(ParseError::at(
+                    input,
+                    line,
+                    "row has different width than the first one",
+                ));
+            }
+            tiles.extend(row);
+        }
+
+        Ok(Grid::new(width, height, tiles))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        match x < self.width && y < self.height {
+            true => self.tiles.get(y * self.width + x),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        match x < self.width && y < self.height {
+            true => self.tiles.get_mut(y * self.width + x),
+            false => None,
+        }
+    }
+
+    /// Position inside the grid that `(x, y)` refers to when the grid repeats as `wrap` says.
+    pub fn resolve(&self, (x, y): (i64, i64), wrap: Wrap) -> Option<(usize, usize)> {
+        let (wrap_x, wrap_y) = match wrap {
+            Wrap::None => (false, false),
+            Wrap::X => (true, false),
+            Wrap::Y => (false, true),
+            Wrap::Both => (true, true),
+        };
+        let axis = |n: i64, len: usize, wrap: bool| match (wrap, len) {
+            (_, 0) => None,
+            (true, len) => Some(n.rem_euclid(len as i64) as usize),
+            (false, len) if (0..len as i64).contains(&n) => Some(n as usize),
+            (false, _) => None,
+        };
+
+        Some((axis(x, self.width, wrap_x)?, axis(y, self.height, wrap_y)?))
+    }
+
+    /// Tile at a position that may lie outside of the grid, see [`Grid::resolve`].
+    pub fn get_wrapping(&self, pos: (i64, i64), wrap: Wrap) -> Option<&T> {
+        self.get(self.resolve(pos, wrap)?)
+    }
+
+    /// Every tile together with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(i, tile)| ((i % width, i / width), tile))
+    }
+
+    /// Panics when `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row out of bounds");
+        &self.tiles[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Panics when `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column out of bounds");
+        self.tiles.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Positions above, right of, below and left of `pos` that are inside the grid.
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors(pos, &NEIGHBORS4)
+    }
+
+    /// Positions of the up to eight tiles surrounding `pos`, clockwise from the top left.
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors(pos, &NEIGHBORS8)
+    }
+
+    fn neighbors(
+        &self,
+        (x, y): (usize, usize),
+        offsets: &'static [(i64, i64)],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets
+            .iter()
+            .filter_map(move |(dx, dy)| self.resolve((x as i64 + dx, y as i64 + dy), Wrap::None))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Mirrors the grid along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(x, y)| self[(y, x)].clone())
+    }
+
+    /// Rotates the grid by a quarter turn clockwise.
+    pub fn rotate_right(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(x, y)| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    /// Rotates the grid by a quarter turn counterclockwise.
+    pub fn rotate_left(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(x, y)| {
+            self[(self.width - 1 - y, x)].clone()
+        })
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |(x, y)| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |(x, y)| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos).expect("position out of bounds")
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        self.get_mut(pos).expect("position out of bounds")
+    }
+}
+
+/// Renders one line per row, with the characters the tiles convert to.
+impl<T: Copy + Into<char>> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            let line: String = row.iter().map(|&tile| tile.into()).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(input: &str) -> Grid<char> {
+        Grid::parse(input, Some, "anything").unwrap()
+    }
+
+    #[test]
+    fn parses_and_renders() {
+        let map: Grid<bool> = Grid::parse(
+            ".#.\n##.\n",
+            |c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            },
+            "'.' or '#'",
+        )
+        .unwrap();
+
+        assert_eq!((map.width(), map.height()), (3, 2));
+        assert_eq!(map.get((1, 0)), Some(&true));
+        assert_eq!(map.get((3, 0)), None);
+
+        let err = Grid::parse(".#\n#x\n", |c| Some(c).filter(|&c| c != 'x'), "no 'x'");
+        assert_eq!(err.map_err(|err| (err.line, err.column)), Err((2, 2)));
+
+        let err = Grid::parse(".#\n#\n", Some, "anything").unwrap_err();
+        assert_eq!(err.message, "row has different width than the first one");
+
+        assert_eq!(grid("ab\ncd\n").to_string(), "ab\ncd\n");
+    }
+
+    #[test]
+    fn wrapping() {
+        let map = grid("ab\ncd\n");
+
+        assert_eq!(map.get_wrapping((-1, 0), Wrap::None), None);
+        assert_eq!(map.get_wrapping((-1, 0), Wrap::X), Some(&'b'));
+        assert_eq!(map.get_wrapping((2, 3), Wrap::X), None);
+        assert_eq!(map.get_wrapping((1, 3), Wrap::Y), Some(&'d'));
+        assert_eq!(map.get_wrapping((5, -2), Wrap::Both), Some(&'b'));
+    }
+
+    #[test]
+    fn rows_columns_and_neighbors() {
+        let map = grid("abc\ndef\n");
+
+        assert_eq!(map.row(1), ['d', 'e', 'f']);
+        assert_eq!(map.rows().count(), 2);
+        assert_eq!(map.column(2).collect::<String>(), "cf");
+        assert_eq!(
+            map.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+
+        assert_eq!(map.neighbors4((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(map.neighbors8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn transformations() {
+        let map = grid("abc\ndef\n");
+
+        assert_eq!(map.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(map.rotate_right().to_string(), "da\neb\nfc\n");
+        assert_eq!(map.rotate_left().to_string(), "cf\nbe\nad\n");
+        assert_eq!(map.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(map.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(map.rotate_right().rotate_left(), map);
+    }
+}
@@ -0,0 +1,370 @@
+//! Just enough HTTP to talk to the puzzle server.
+//!
+//! Plain `http://` URLs are requested over a `TcpStream`. Std has no TLS, so `https://` ones are
+//! delegated to the `curl` binary.
+
+use crate::config::Config;
+use crate::AocError;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const USER_AGENT: &str = concat!(
+    "aoc/",
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CARGO_PKG_AUTHORS"),
+    ")"
+);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+pub fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, AocError> {
+    let context = || format!("{} {} failed", method, url);
+
+    let response = match url.strip_prefix("http://") {
+        Some(rest) => plain_request(method, rest, headers, body),
+        None => curl_request(method, url, headers, body),
+    };
+
+    response.map_err(|err| AocError::io(context(), err))
+}
+
+fn plain_request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    let (host, path) = match url.find('/') {
+        Some(i) => url.split_at(i),
+        None => (url, "/"),
+    };
+    let address = match host.contains(':') {
+        true => host.to_owned(),
+        false => format!("{}:80", host),
+    };
+
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+
+    // HTTP/1.0 so the server closes the connection and never uses chunked encoding.
+    let mut request = format!(
+        "{} {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}\r\n",
+        method, path, host, USER_AGENT
+    );
+    for (name, value) in headers {
+        request += &format!("{}: {}\r\n", name, value);
+    }
+    let body = body.unwrap_or("");
+    request += &format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response");
+    let (head, body) = crate::prelude::split_once(&response, "\r\n\r\n").ok_or_else(invalid)?;
+    let status = head
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+
+    Ok(Response {
+        status,
+        body: body.to_owned(),
+    })
+}
+
+fn curl_request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    // Headers carry the session token, so they're passed in a config on stdin instead of as
+    // arguments, which any local user can read.
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--request", method])
+        .args(["--user-agent", USER_AGENT])
+        .args(["--write-out", "\n%{http_code}"])
+        .args(["--config", "-"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(curl_config(headers, body).as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        return Err(io::Error::other(message));
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = output.rsplit_once('\n').unwrap_or(("", &output));
+
+    Ok(Response {
+        status: status
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "malformed curl output"))?,
+        body: body.to_owned(),
+    })
+}
+
+/// Keeps requests at least `interval` apart, even across runs, by remembering the time of the
+/// last one in a file. Threads of one run take turns, so they can't both read the same stamp.
+pub struct RateLimit {
+    pub stamp: PathBuf,
+    pub interval: Duration,
+}
+
+impl RateLimit {
+    pub fn wait(&self) -> Result<(), AocError> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        let _turn = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let last = std::fs::read_to_string(&self.stamp)
+            .ok()
+            .and_then(|stamp| stamp.trim().parse().ok())
+            .map(Duration::from_millis);
+
+        if let Some(remaining) = last.and_then(|last| (last + self.interval).checked_sub(now)) {
+            std::thread::sleep(remaining);
+        }
+
+        let now = now.max(last.map_or(now, |last| last + self.interval));
+        std::fs::write(&self.stamp, now.as_millis().to_string())
+            .map_err(|err| AocError::io(format!("couldn't write {}", self.stamp.display()), err))
+    }
+}
+
+/// Authenticated, rate limited requests to the puzzle server.
+pub struct Client {
+    pub base_url: String,
+    pub session: String,
+    pub rate_limit: RateLimit,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Client, AocError> {
+        Ok(Client {
+            base_url: config.base_url.clone(),
+            session: config.session()?.to_owned(),
+            rate_limit: RateLimit {
+                stamp: PathBuf::from(".aoc-last-request"),
+                interval: config.request_interval,
+            },
+        })
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, AocError> {
+        self.send("GET", path, None)
+    }
+
+    /// Posts an `application/x-www-form-urlencoded` body.
+    pub fn post(&self, path: &str, form: &str) -> Result<String, AocError> {
+        self.send("POST", path, Some(form))
+    }
+
+    fn send(&self, method: &str, path: &str, body: Option<&str>) -> Result<String, AocError> {
+        self.rate_limit.wait()?;
+
+        let url = format!("{}{}", self.base_url, path);
+        let cookie = format!("session={}", self.session);
+        let mut headers = vec![("Cookie", cookie.as_str())];
+        if body.is_some() {
+            headers.push(("Content-Type", "application/x-www-form-urlencoded"));
+        }
+
+        let response = request(method, &url, &headers, body)?;
+
+        match response.status {
+            200..=299 => Ok(response.body),
+            status => Err(AocError::io(
+                format!("{} {} failed", method, url),
+                io::Error::other(format!(
+                    "server responded with {}: {}",
+                    status,
+                    response.body.trim()
+                )),
+            )),
+        }
+    }
+}
+
+/// curl config with the headers and body of a request.
+fn curl_config(headers: &[(&str, &str)], body: Option<&str>) -> String {
+    let quote = |value: &str| {
+        let mut quoted = String::from("\"");
+        for c in value.chars() {
+            match c {
+                '"' => quoted += "\\\"",
+                '\\' => quoted += "\\\\",
+                '\n' => quoted += "\\n",
+                '\r' => quoted += "\\r",
+                '\t' => quoted += "\\t",
+                c => quoted.push(c),
+            }
+        }
+        quoted + "\""
+    };
+
+    let mut config = String::new();
+    for (name, value) in headers {
+        config += &format!("header = {}\n", quote(&format!("{}: {}", name, value)));
+    }
+    if let Some(body) = body {
+        // Unlike `data-binary`, `data-raw` doesn't read a file when the body starts with `@`.
+        config += &format!("data-raw = {}\n", quote(body));
+    }
+    config
+}
+
+/// Local stand-in for the puzzle server, answering each connection with the next of `responses`
+/// and returning the requests it received once all were answered.
+#[cfg(test)]
+pub(crate) fn stand_in(
+    responses: Vec<(u16, &'static str)>,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let server = std::thread::spawn(move || {
+        responses
+            .into_iter()
+            .map(|(status, body)| {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 4096];
+
+                // Reads the head and a body of the announced length.
+                loop {
+                    let n = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = crate::prelude::split_once(&text, "\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("Content-Length: "))
+                            .map_or(0, |length| length.parse().unwrap());
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+
+                write!(
+                    stream,
+                    "HTTP/1.0 {} Stand-in\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+
+                String::from_utf8(request).unwrap()
+            })
+            .collect()
+    });
+
+    (url, server)
+}
+
+/// Empty directory for the files of the test `name`, unique to this process.
+#[cfg(test)]
+pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Client for a [`stand_in`] at `url` that never waits, together with the [`scratch_dir`] that
+/// keeps its rate limit stamp.
+#[cfg(test)]
+pub(crate) fn stand_in_client(name: &str, url: String) -> (Client, PathBuf) {
+    let dir = scratch_dir(name);
+    let client = Client {
+        base_url: url,
+        session: "secret".to_owned(),
+        rate_limit: RateLimit {
+            stamp: dir.join("stamp"),
+            interval: Duration::from_secs(0),
+        },
+    };
+    (client, dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_http() {
+        let (url, server) = stand_in(vec![(200, "hello"), (404, "missing")]);
+        let (client, dir) = stand_in_client("plain-http", url);
+
+        assert_eq!(client.post("/greet", "name=x").unwrap(), "hello");
+        assert!(matches!(client.get("/missing"), Err(AocError::Io { .. })));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /greet HTTP/1.0\r\n"));
+        assert!(requests[0].contains("Cookie: session=secret\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nname=x"));
+        assert!(requests[1].starts_with("GET /missing "));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rate_limit_across_threads() {
+        let dir = scratch_dir("rate-limit");
+        let rate_limit = RateLimit {
+            stamp: dir.join("stamp"),
+            interval: Duration::from_millis(50),
+        };
+
+        let start = std::time::Instant::now();
+        std::thread::scope(|scope| {
+            for _ in 0..3 {
+                scope.spawn(|| rate_limit.wait().unwrap());
+            }
+        });
+        // The stamp only keeps whole milliseconds.
+        assert!(start.elapsed() >= Duration::from_millis(98));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn curl_config_quotes() {
+        let config = curl_config(&[("Cookie", "session=s\"1")], Some("a=\\\n"));
+        assert_eq!(
+            config,
+            "header = \"Cookie: session=s\\\"1\"\ndata-raw = \"a=\\\\\\n\"\n"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::config::Config;
+use crate::http::Client;
+use crate::AocError;
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Where the puzzle input of a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `input/<year>_<day>.txt` relative to the current directory, downloaded from the puzzle
+    /// server when it doesn't exist yet.
+    Default,
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    /// Interprets a command line argument, `-` meaning stdin.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn load(&self, year: u16, day: u8) -> Result<String, AocError> {
+        match self {
+            InputSource::Default => {
+                let path = default_path(year, day);
+                cached(&path, || {
+                    Client::new(&Config::load()?)
+                        .and_then(|client| fetch(&client, year, day))
+                        .map_err(|err| missing(&path, err))
+                })
+            }
+            InputSource::File(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|err| AocError::io("couldn't read input from stdin", err))?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// Undoes what editors and downloads do to inputs, so solutions only have to handle `\n`
+/// terminated lines: drops a byte order mark, turns CRLF line endings into LF and replaces
+/// trailing blank lines with a single line ending.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut end = 0;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            end = offset + line.trim_end_matches(['\r', '\n']).len();
+        }
+        offset += line.len();
+    }
+
+    let content = &input[..end];
+    match content.contains('\r') {
+        false if content.is_empty() => Cow::Borrowed(""),
+        false if input[end..].starts_with('\n') => Cow::Borrowed(&input[..=end]),
+        false => Cow::Owned(format!("{}\n", content)),
+        true => Cow::Owned(content.replace("\r\n", "\n") + "\n"),
+    }
+}
+
+pub fn default_path(year: u16, day: u8) -> PathBuf {
+    PathBuf::from(format!("input/{}_{:02}.txt", year, day))
+}
+
+/// Every `examples/<year>_<day>_*.txt`, sorted by name.
+pub fn example_paths(year: u16, day: u8) -> Vec<PathBuf> {
+    let prefix = format!("{}_{:02}_", year, day);
+
+    let mut examples: Vec<_> = std::fs::read_dir("examples")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+        })
+        .collect();
+    examples.sort();
+    examples
+}
+
+/// Downloads an input, which is an error when it's empty so it never gets cached.
+pub fn fetch(client: &Client, year: u16, day: u8) -> Result<String, AocError> {
+    let input = client.get(&format!("/{}/day/{}/input", year, day))?;
+
+    match input.is_empty() {
+        true => Err(AocError::io(
+            format!("couldn't download input of {} day {}", year, day),
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "the input is empty"),
+        )),
+        false => Ok(input),
+    }
+}
+
+/// Reads `path`, or stores what `fetch` returns there first. Cached files are never fetched again,
+/// except for empty ones like the placeholders `aoc new` creates.
+pub fn cached(
+    path: &Path,
+    fetch: impl FnOnce() -> Result<String, AocError>,
+) -> Result<String, AocError> {
+    if std::fs::metadata(path).is_ok_and(|meta| meta.len() > 0) {
+        return read_file(path);
+    }
+
+    let input = fetch()?;
+    let context = || format!("couldn't cache input in {}", path.display());
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| AocError::io(context(), err))?;
+    }
+    std::fs::write(path, &input).map_err(|err| AocError::io(context(), err))?;
+
+    Ok(input)
+}
+
+/// Names the input that couldn't be downloaded, so running in the wrong directory doesn't look
+/// like a problem with the session token or the puzzle server.
+fn missing(path: &Path, err: AocError) -> AocError {
+    let missing = format!("{} is missing and couldn't be downloaded", path.display());
+
+    match err {
+        AocError::Config(message) => AocError::Config(format!("{}: {}", missing, message)),
+        AocError::Io { context, source } => {
+            AocError::io(format!("{}: {}", missing, context), source)
+        }
+        err => err,
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, AocError> {
+    std::fs::read_to_string(path)
+        .map_err(|err| AocError::io(format!("couldn't read input file {}", path.display()), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{stand_in, stand_in_client};
+
+    #[test]
+    fn normalizes() {
+        assert_eq!(normalize("\u{feff}a\r\n\r\nb\r\n  \r\n\n"), "a\n\nb\n");
+        assert_eq!(normalize("a\nb"), "a\nb\n");
+        assert_eq!(normalize("\n \n"), "");
+        assert!(matches!(normalize("a\n\nb\n\n"), Cow::Borrowed("a\n\nb\n")));
+    }
+
+    #[test]
+    fn names_missing_input() {
+        let path = default_path(2020, 3);
+        let err = missing(&path, AocError::Config("no session token".to_owned()));
+
+        assert_eq!(
+            err.to_string(),
+            "input/2020_03.txt is missing and couldn't be downloaded: no session token"
+        );
+        assert_eq!(err.exit_code(), 6);
+    }
+
+    #[test]
+    fn fetches_missing_input_once() {
+        let (url, server) = stand_in(vec![(200, ""), (200, "1\n2\n")]);
+        let (client, dir) = stand_in_client("fetch", url);
+        let path = dir.join("2020_01.txt");
+
+        // Empty files are placeholders, and empty downloads don't replace them.
+        std::fs::write(&path, "").unwrap();
+        assert!(cached(&path, || fetch(&client, 2020, 1)).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+        for _ in 0..2 {
+            let input = cached(&path, || fetch(&client, 2020, 1)).unwrap();
+            assert_eq!(input, "1\n2\n");
+        }
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].starts_with("GET /2020/day/1/input "));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
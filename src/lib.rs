@@ -1,69 +1,259 @@
+pub use error::AocError;
+pub use random::Rng;
+use std::any::Any;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Part {
     One,
     Two,
 }
 
-pub fn part() -> Part {
-    match std::env::args().nth(1) {
-        Some(s) if s == "--part2" => Part::Two,
-        _ => Part::One,
+impl Part {
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
     }
 }
 
-pub mod prelude {
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Part {}", self.number())
+    }
+}
 
-    pub fn split_once<'a>(s: &'a str, delimiter: &'_ str) -> Option<(&'a str, &'a str)> {
-        let mid = s.find(delimiter)?;
+pub mod answers;
+pub mod cli;
+pub mod config;
+pub mod diagnostic;
+pub mod error;
+pub mod grid;
+pub mod http;
+pub mod input;
+pub mod json;
+pub mod parser;
+pub mod random;
+pub mod runner;
+pub mod scaffold;
+pub mod status;
+pub mod submit;
+pub mod watch;
 
-        Some((&s[..mid], &s[mid + delimiter.len()..]))
+#[path = "2019/mod.rs"]
+pub mod y2019;
+#[path = "2020/mod.rs"]
+pub mod y2020;
+
+pub trait Solution {
+    type Input;
+    type Answer: std::fmt::Display;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer, AocError>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer, AocError>;
+
+    /// Other implementations of the parts, compared with `part1` and `part2` by `--cross-check`.
+    fn alternatives() -> Vec<Alternative<Self>> {
+        Vec::new()
     }
 
-    use std::fmt::Display;
-    pub trait UserErrorMessageExit<T> {
-        fn or_exit(self) -> T;
-        fn or_exit_with(self, msg: impl Display) -> T;
+    /// Random valid input for `--cross-check`.
+    fn generate(_rng: &mut Rng) -> Option<String> {
+        None
     }
+}
 
-    impl<T, E: Display> UserErrorMessageExit<T> for Result<T, E> {
-        fn or_exit(self) -> T {
-            match self {
-                Result::Ok(v) => v,
-                Result::Err(err) => {
-                    eprintln!("{}", err);
-                    std::process::exit(1);
-                }
-            }
-        }
+/// Another implementation of a part, e.g. a brute force one to check an optimized one against.
+pub struct Alternative<S: Solution + ?Sized> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: fn(&S::Input) -> Result<S::Answer, AocError>,
+}
 
-        fn or_exit_with(self, msg: impl Display) -> T {
-            match self {
-                Result::Ok(v) => v,
-                Result::Err(err) => {
-                    eprintln!("{}: {}", msg, err);
-                    std::process::exit(0);
-                }
-            }
-        }
+/// Name of the `part1` and `part2` implementations when listed with alternatives.
+pub const MAIN_IMPLEMENTATION: &str = "main";
+
+/// Object safe counterpart of [`Solution`] so days with different input types can share a registry.
+/// Inputs are [normalized](input::normalize) before they're handed to the solution.
+///
+/// Solutions are `Sync` so the registry can be shared by the threads of `aoc run-all`.
+pub trait DynSolution: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError>;
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<String, AocError>;
+    /// Answers of the main implementation of `part` followed by its alternatives.
+    fn solve_all(
+        &self,
+        input: &dyn Any,
+        part: Part,
+    ) -> Vec<(&'static str, Result<String, AocError>)>;
+    /// Names of the alternatives of `part`.
+    fn alternatives(&self, part: Part) -> Vec<&'static str>;
+    /// Answer of the alternative of `part` called `name`, if there is one.
+    fn solve_alternative(
+        &self,
+        input: &dyn Any,
+        part: Part,
+        name: &str,
+    ) -> Option<Result<String, AocError>>;
+    fn generate(&self, rng: &mut Rng) -> Option<String>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError> {
+        let input = S::parse(&input::normalize(input))?;
+        Ok(Box::new(input))
     }
 
-    impl<T> UserErrorMessageExit<T> for Option<T> {
-        fn or_exit(self) -> T {
-            match self {
-                Option::Some(v) => v,
-                Option::None => {
-                    std::process::exit(0);
-                }
-            }
-        }
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<String, AocError> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input parsed by a different solution");
+
+        let answer = match part {
+            Part::One => S::part1(input),
+            Part::Two => S::part2(input),
+        };
+
+        answer.map(|a| a.to_string())
+    }
+
+    fn solve_all(
+        &self,
+        input: &dyn Any,
+        part: Part,
+    ) -> Vec<(&'static str, Result<String, AocError>)> {
+        let main = (MAIN_IMPLEMENTATION, self.solve(input, part));
+        let alternatives = self
+            .alternatives(part)
+            .into_iter()
+            .filter_map(|name| Some((name, self.solve_alternative(input, part, name)?)));
+
+        std::iter::once(main).chain(alternatives).collect()
+    }
+
+    fn alternatives(&self, part: Part) -> Vec<&'static str> {
+        S::alternatives()
+            .iter()
+            .filter(|alternative| alternative.part == part)
+            .map(|alternative| alternative.name)
+            .collect()
+    }
+
+    fn solve_alternative(
+        &self,
+        input: &dyn Any,
+        part: Part,
+        name: &str,
+    ) -> Option<Result<String, AocError>> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input parsed by a different solution");
+
+        S::alternatives()
+            .into_iter()
+            .find(|alternative| alternative.part == part && alternative.name == name)
+            .map(|alternative| (alternative.solve)(input).map(|a| a.to_string()))
+    }
+
+    fn generate(&self, rng: &mut Rng) -> Option<String> {
+        S::generate(rng)
+    }
+}
+
+/// Runs `S` on an example input and asserts the answer of `part`, used by [`examples!`].
+#[doc(hidden)]
+pub fn check_example<S: Solution>(input: &str, part: Part, expected: impl std::fmt::Display) {
+    let input = match S::parse(&input::normalize(input)) {
+        Ok(input) => input,
+        Err(err) => panic!("couldn't parse example: {}", err),
+    };
 
-        fn or_exit_with(self, msg: impl Display) -> T {
-            match self {
-                Option::Some(v) => v,
-                Option::None => {
-                    eprintln!("{}", msg);
-                    std::process::exit(0);
+    let answer = match part {
+        Part::One => S::part1(&input),
+        Part::Two => S::part2(&input),
+    };
+
+    assert_eq!(
+        answer.map(|a| a.to_string()).ok(),
+        Some(expected.to_string()),
+        "{} of the example",
+        part
+    );
+
+    for alternative in S::alternatives().iter().filter(|a| a.part == part) {
+        assert_eq!(
+            (alternative.solve)(&input).map(|a| a.to_string()).ok(),
+            Some(expected.to_string()),
+            "{} of the example using {}",
+            part,
+            alternative.name
+        );
+    }
+}
+
+/// Generates a test for every `name: (example, part, expected)` entry, where `example` names
+/// the file `examples/<example>.txt`.
+///
+/// ```ignore
+/// examples!(Day07 {
+///     part1: ("2020_07_1", One, 4),
+///     part2: ("2020_07_2", Two, 126),
+/// });
+/// ```
+#[macro_export]
+macro_rules! examples {
+    ($solution:ty { $($name:ident: ($example:literal, $part:ident, $expected:expr)),* $(,)? }) => {
+        #[cfg(test)]
+        mod examples {
+            use super::*;
+
+            $(
+                #[test]
+                fn $name() {
+                    let input = include_str!(concat!(
+                        env!("CARGO_MANIFEST_DIR"),
+                        "/examples/",
+                        $example,
+                        ".txt"
+                    ));
+                    $crate::check_example::<$solution>(input, $crate::Part::$part, $expected);
                 }
-            }
+            )*
         }
+    };
+}
+
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub solution: &'static dyn DynSolution,
+}
+
+/// Registries of every year, in order. `aoc new` adds years here.
+const YEARS: &[&[Day]] = &[y2019::DAYS, y2020::DAYS];
+
+pub fn days() -> impl Iterator<Item = &'static Day> {
+    YEARS.iter().flat_map(|days| days.iter())
+}
+
+pub fn find_day(year: u16, day: u8) -> Option<&'static Day> {
+    days().find(|d| d.year == year && d.day == day)
+}
+
+pub mod prelude {
+    pub use crate::diagnostic::ParseError;
+    pub use crate::error::AocError;
+    pub use crate::parser::{self, PResult};
+    pub use crate::{Alternative, Part, Rng};
+
+    pub fn split_once<'a>(s: &'a str, delimiter: &'_ str) -> Option<(&'a str, &'a str)> {
+        let mid = s.find(delimiter)?;
+
+        Some((&s[..mid], &s[mid + delimiter.len()..]))
     }
 }
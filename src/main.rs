@@ -0,0 +1,464 @@
+use advent_of_code::answers::Answers;
+use advent_of_code::cli::{Command, DrawOptions, Output, RunOptions, UsageError, USAGE};
+use advent_of_code::config::Config;
+use advent_of_code::http::Client;
+use advent_of_code::input::InputSource;
+use advent_of_code::submit::History;
+use advent_of_code::y2020::day03::{self, Map};
+use advent_of_code::{runner, scaffold, status, submit, watch, AocError, Day, Part, Rng};
+use std::path::Path;
+use std::time::Duration;
+
+fn main() {
+    if let Err(err) = run_command() {
+        eprintln!("{}", err);
+        std::process::exit(err.exit_code());
+    }
+}
+
+fn run_command() -> Result<(), AocError> {
+    match Command::parse(std::env::args().skip(1))? {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Submit { year, day, part } => submit(year, day, part),
+        Command::Generate { year, day, seed } => generate(year, day, seed),
+        Command::Slopes { input, max, top } => slopes(input, max, top),
+        Command::DrawSlopes(options) => draw_slopes(options),
+        Command::Run(options) => run(options),
+        Command::CheckAll => check_all(),
+        Command::RunAll { threads } => run_all(threads),
+        Command::Status => status(),
+        Command::Watch { year, day } => watch(year, day),
+        Command::New { year, day } => {
+            for path in scaffold::new_day(Path::new("."), year, day)? {
+                println!("created {}", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn find_day(year: u16, day: u8) -> Result<&'static Day, AocError> {
+    advent_of_code::find_day(year, day).ok_or_else(|| {
+        AocError::Usage(UsageError(format!(
+            "there is no solution for {} day {}",
+            year, day
+        )))
+    })
+}
+
+fn run(options: RunOptions) -> Result<(), AocError> {
+    let day = find_day(options.year, options.day)?;
+    let input = options.input.load(day.year, day.day)?;
+
+    if options.cross_check {
+        return cross_check(day, &input, &options.parts);
+    }
+
+    if let Some(iterations) = options.bench {
+        let bench = runner::bench(day, &input, &options.parts, iterations)?;
+
+        println!("Parse: {}", bench.parse);
+        for (part, stats) in bench.parts {
+            println!("{}: {}", part, stats);
+
+            for (_, name, stats) in bench.alternatives.iter().filter(|a| a.0 == part) {
+                println!("{} using {}: {}", part, name, stats);
+            }
+        }
+        return Ok(());
+    }
+
+    let answers = match options.check {
+        true => Some(Answers::load(day.year, day.day)?),
+        false => None,
+    };
+
+    let run = match runner::run(day, &input, &options.parts) {
+        Err(err) if options.output == Output::Json => {
+            for object in runner::failed_json(day, &options.parts, &err) {
+                println!("{}", object);
+            }
+            return Err(err);
+        }
+        run => run?,
+    };
+
+    if options.output == Output::Json {
+        return print_json(day, run, answers);
+    }
+
+    if options.time {
+        println!("Parse: {:.2?}", run.parse_time);
+    }
+
+    let mut regressions = 0;
+    let mut error = None;
+
+    for part in run.parts {
+        if let Some(answers) = &answers {
+            let verdict = answers.check(part.part, part.answer.as_deref().ok());
+            if verdict.is_failure() {
+                regressions += 1;
+            }
+
+            let answer = match &part.answer {
+                Ok(answer) => format!("{} {}", answer, verdict),
+                Err(err) => format!("ERROR {}", err),
+            };
+            if options.time {
+                println!("{}: {} ({:.2?})", part.part, answer, part.time);
+            } else {
+                println!("{}: {}", part.part, answer);
+            }
+            continue;
+        }
+
+        // Like `print_json`, every part gets printed and the first failure is reported afterwards.
+        let answer = match part.answer {
+            Ok(answer) => answer,
+            Err(err) => {
+                error.get_or_insert(err);
+                "ERROR".to_owned()
+            }
+        };
+
+        if options.time {
+            println!("{}: {} ({:.2?})", part.part, answer, part.time);
+        } else {
+            println!("{}: {}", part.part, answer);
+        }
+    }
+
+    if let Some(err) = error {
+        return Err(err);
+    }
+
+    match regressions {
+        0 => Ok(()),
+        n => Err(AocError::Regression(n)),
+    }
+}
+
+fn generate(year: u16, day: u8, seed: u64) -> Result<(), AocError> {
+    let input = find_day(year, day)?
+        .solution
+        .generate(&mut Rng::new(seed))
+        .ok_or_else(|| {
+            AocError::Usage(UsageError(format!(
+                "there is no input generator for {} day {}",
+                year, day
+            )))
+        })?;
+
+    print!("{}", input);
+    Ok(())
+}
+
+fn slopes(input: InputSource, max: i64, top: usize) -> Result<(), AocError> {
+    let input = input.load(2020, 3)?;
+    let map: Map = advent_of_code::input::normalize(&input).parse()?;
+    let ranking = day03::rank_slopes(&map, max, top);
+
+    for (title, slopes) in [("Fewest", ranking.fewest), ("Most", ranking.most)] {
+        println!("{} trees:", title);
+        for ((dir_x, dir_y), trees) in slopes {
+            println!("  right {:>3}, down {:>3}: {}", dir_x, dir_y, trees);
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_slopes(options: DrawOptions) -> Result<(), AocError> {
+    let input = options.input.load(2020, 3)?;
+    let map: Map = advent_of_code::input::normalize(&input).parse()?;
+
+    let slopes = options
+        .slopes
+        .iter()
+        .map(|&(right, down)| map.slope(right, down).with_edge(options.edge))
+        .collect();
+    let drawing = day03::draw(&map, slopes);
+
+    match &options.ppm {
+        Some(path) => std::fs::write(path, day03::ppm(&drawing, options.scale))
+            .map_err(|err| AocError::io(format!("couldn't write {}", path.display()), err)),
+        None => {
+            print!("{}", day03::ansi(&drawing));
+            Ok(())
+        }
+    }
+}
+
+/// Number of generated inputs `--cross-check` runs on besides the real one.
+const GENERATED_INPUTS: u64 = 10;
+
+fn cross_check(day: &Day, input: &str, parts: &[Part]) -> Result<(), AocError> {
+    let mut inputs = vec![("input".to_owned(), input.to_owned())];
+    for seed in 1..=GENERATED_INPUTS {
+        if let Some(input) = day.solution.generate(&mut Rng::new(seed)) {
+            inputs.push((format!("seed {}", seed), input));
+        }
+    }
+
+    let mut mismatches = 0;
+
+    for (name, input) in inputs {
+        let checks = match runner::cross_check(day, &input, parts) {
+            Ok(checks) => checks,
+            Err(err) => {
+                mismatches += 1;
+                println!("{}: ERROR {}", name, err);
+                continue;
+            }
+        };
+
+        for check in checks {
+            let show = |answer: &Result<String, AocError>| match answer {
+                Ok(answer) => answer.clone(),
+                Err(err) => format!("ERROR {}", err),
+            };
+
+            if check.agrees() {
+                let answer = show(&check.answers[0].1);
+                match check.answers.len() {
+                    1 => println!("{} {}: {}", name, check.part, answer),
+                    n => println!(
+                        "{} {}: {} ({} implementations agree)",
+                        name, check.part, answer, n
+                    ),
+                }
+            } else {
+                mismatches += 1;
+                let answers: Vec<_> = check
+                    .answers
+                    .iter()
+                    .map(|(implementation, answer)| format!("{} {}", implementation, show(answer)))
+                    .collect();
+                println!("{} {}: MISMATCH {}", name, check.part, answers.join(", "));
+            }
+        }
+    }
+
+    match mismatches {
+        0 => Ok(()),
+        n => Err(AocError::Regression(n)),
+    }
+}
+
+/// Prints every part, even when some of them failed, and reports the first failure afterwards.
+fn print_json(day: &Day, run: runner::Run, answers: Option<Answers>) -> Result<(), AocError> {
+    let mut regressions = 0;
+
+    for (object, part) in run.to_json(day).into_iter().zip(&run.parts) {
+        let object = match &answers {
+            Some(answers) => {
+                let verdict = answers.check(part.part, part.answer.as_deref().ok());
+                if verdict.is_failure() {
+                    regressions += 1;
+                }
+                object.with("verdict", verdict.to_string())
+            }
+            None => object,
+        };
+
+        println!("{}", object);
+    }
+
+    if let Some(err) = run.parts.into_iter().find_map(|part| part.answer.err()) {
+        return Err(err);
+    }
+
+    match regressions {
+        0 => Ok(()),
+        n => Err(AocError::Regression(n)),
+    }
+}
+
+fn submit(year: u16, day: u8, part: Part) -> Result<(), AocError> {
+    let day = find_day(year, day)?;
+    let input = InputSource::Default.load(day.year, day.day)?;
+    let client = Client::new(&Config::load()?)?;
+
+    let run = runner::run(day, &input, &[part])?;
+    for part in run.parts {
+        let answer = part.answer?;
+        let history = History::path(day.year, day.day);
+        let outcome = submit::submit(&client, &history, day.year, day.day, part.part, &answer)?;
+
+        println!("{}: {} {}", part.part, answer, outcome);
+    }
+
+    Ok(())
+}
+
+fn run_all(threads: Option<usize>) -> Result<(), AocError> {
+    let threads = threads
+        .or_else(|| std::thread::available_parallelism().map(usize::from).ok())
+        .unwrap_or(1);
+    let days: Vec<_> = advent_of_code::days().collect();
+
+    let (results, wall_time) = runner::measure(|| {
+        runner::parallel(&days, threads, |day| {
+            let input = InputSource::Default.load(day.year, day.day)?;
+            let answers = Answers::load(day.year, day.day)?;
+            let run = runner::run(day, &input, &[Part::One, Part::Two])?;
+            Ok::<_, AocError>((answers, run))
+        })
+    });
+
+    let mut rows = vec![[
+        "Day".to_owned(),
+        "Part".to_owned(),
+        "Answer".to_owned(),
+        "Parse".to_owned(),
+        "Solve".to_owned(),
+        "Check".to_owned(),
+    ]];
+    let mut total = Duration::default();
+    let mut regressions = 0;
+
+    for (day, result) in days.iter().zip(results) {
+        let name = format!("{} {:02}", day.year, day.day);
+
+        let (answers, run) = match result {
+            Ok(result) => result,
+            Err(err) => {
+                regressions += 1;
+                let error = err.to_string().lines().next().unwrap_or("").to_owned();
+                let dash = || "-".to_owned();
+                rows.push([name, dash(), error, dash(), dash(), "ERROR".to_owned()]);
+                continue;
+            }
+        };
+
+        total += run.parse_time;
+
+        for part in run.parts {
+            let verdict = answers.check(part.part, part.answer.as_deref().ok());
+            if verdict.is_failure() {
+                regressions += 1;
+            }
+            total += part.time;
+
+            let answer = match part.answer {
+                Ok(answer) => answer,
+                Err(err) => err.to_string().lines().next().unwrap_or("").to_owned(),
+            };
+            rows.push([
+                name.clone(),
+                part.part.number().to_string(),
+                answer,
+                format!("{:.2?}", run.parse_time),
+                format!("{:.2?}", part.time),
+                verdict.to_string(),
+            ]);
+        }
+    }
+
+    let widths: Vec<_> = (0..6)
+        .map(|column| rows.iter().map(|row| row[column].chars().count()).max())
+        .map(|width| width.unwrap_or(0))
+        .collect();
+
+    for row in &rows {
+        let cells: Vec<_> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+
+    println!();
+    println!(
+        "Total: {:.2?} ({:.2?} wall clock on {} thread(s))",
+        total, wall_time, threads
+    );
+
+    match regressions {
+        0 => Ok(()),
+        n => Err(AocError::Regression(n)),
+    }
+}
+
+fn status() -> Result<(), AocError> {
+    for year in status::years() {
+        let days = (1..=25)
+            .map(|day| status::DayStatus::load(year, day))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        println!("{}", status::calendar(year, &days));
+    }
+    println!("{}", status::LEGEND);
+
+    Ok(())
+}
+
+fn watch(year: u16, day: u8) -> Result<(), AocError> {
+    let day = find_day(year, day)?;
+    let mut watcher = watch::Watcher::default();
+    let mut previous = Vec::new();
+
+    loop {
+        let files = watch::day_files(day.year, day.day);
+
+        if watcher.poll(files.clone()) {
+            let answers = watch::evaluate(day, &files);
+
+            println!("--- {} day {:02} ---", day.year, day.day);
+            for line in watch::report(&previous, &answers) {
+                println!("{}", line);
+            }
+
+            previous = answers;
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn check_all() -> Result<(), AocError> {
+    let mut regressions = 0;
+
+    for day in advent_of_code::days() {
+        let result = InputSource::Default
+            .load(day.year, day.day)
+            .and_then(|input| {
+                let answers = Answers::load(day.year, day.day)?;
+                let run = runner::run(day, &input, &[Part::One, Part::Two])?;
+                Ok((answers, run))
+            });
+
+        let (answers, run) = match result {
+            Ok(result) => result,
+            Err(err) => {
+                regressions += 1;
+                println!("{} day {:02}: ERROR {}", day.year, day.day, err);
+                continue;
+            }
+        };
+
+        for part in run.parts {
+            let verdict = answers.check(part.part, part.answer.as_deref().ok());
+            if verdict.is_failure() {
+                regressions += 1;
+            }
+            match part.answer {
+                Err(err) => println!(
+                    "{} day {:02} {}: ERROR {}",
+                    day.year, day.day, part.part, err
+                ),
+                Ok(_) => println!("{} day {:02} {}: {}", day.year, day.day, part.part, verdict),
+            }
+        }
+    }
+
+    match regressions {
+        0 => Ok(()),
+        n => Err(AocError::Regression(n)),
+    }
+}
@@ -0,0 +1,362 @@
+//! Small parser combinators.
+//!
+//! A parser is any `Fn(&str) -> PResult<T>` returning the parsed value together with the rest of
+//! the input. Failures keep the slice of the input they occurred at, so [`parse`] can turn them
+//! into a [`ParseError`] pointing at the right line and column.
+
+use crate::diagnostic::ParseError;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub struct Failure<'a> {
+    /// Rest of the input where the parser failed.
+    pub at: &'a str,
+    pub message: String,
+}
+
+impl<'a> Failure<'a> {
+    pub fn new(at: &'a str, message: impl Into<String>) -> Failure<'a> {
+        Failure {
+            at,
+            message: message.into(),
+        }
+    }
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+/// Runs `parser` on `input`, which has to be consumed completely.
+pub fn parse<'a, T>(
+    input: &'a str,
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+) -> Result<T, ParseError> {
+    parser(input)
+        .and_then(|(value, rest)| end(rest).map(|_| value))
+        .map_err(|failure| ParseError::at(input, failure.at, failure.message))
+}
+
+pub fn end(input: &str) -> PResult<'_, ()> {
+    match input.chars().next() {
+        None => Ok(((), input)),
+        Some(c) => Err(Failure::new(input, format!("unexpected '{}'", c))),
+    }
+}
+
+pub fn literal<'a>(literal: &'static str) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(literal) {
+        Some(rest) => Ok((&input[..literal.len()], rest)),
+        None => Err(Failure::new(input, format!("expected '{}'", literal))),
+    }
+}
+
+pub fn char_where<'a>(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Fn(&'a str) -> PResult<'a, char> {
+    move |input: &'a str| match input.chars().next() {
+        Some(c) if predicate(c) => Ok((c, &input[c.len_utf8()..])),
+        _ => Err(Failure::new(input, format!("expected {}", expected))),
+    }
+}
+
+pub fn take_while<'a>(
+    predicate: impl Fn(char) -> bool,
+) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input: &'a str| {
+        let end = input.find(|c| !predicate(c)).unwrap_or(input.len());
+        Ok((&input[..end], &input[end..]))
+    }
+}
+
+/// Like [`take_while`] but fails when nothing matches.
+pub fn take_while1<'a>(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    let take = take_while(predicate);
+    move |input: &'a str| match take(input)? {
+        ("", _) => Err(Failure::new(input, format!("expected {}", expected))),
+        result => Ok(result),
+    }
+}
+
+/// Everything up to `delimiter`, which is left in the input.
+pub fn until<'a>(delimiter: &'static str) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input: &'a str| match input.find(delimiter) {
+        Some(end) => Ok((&input[..end], &input[end..])),
+        None => Err(Failure::new(input, format!("expected '{}'", delimiter))),
+    }
+}
+
+pub fn rest(input: &str) -> PResult<'_, &str> {
+    Ok((input, &input[input.len()..]))
+}
+
+/// Spaces and tabs, possibly none.
+pub fn whitespace(input: &str) -> PResult<'_, &str> {
+    take_while(|c| c == ' ' || c == '\t')(input)
+}
+
+/// Decimal integer with an optional `+` or `-` sign.
+pub fn integer<'a, T: FromStr>() -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input: &'a str| {
+        let sign = input.starts_with(['+', '-']) as usize;
+        let (digits, rest) = take_while1(|c| c.is_ascii_digit(), "a number")(&input[sign..])
+            .map_err(|failure| Failure::new(input, failure.message))?;
+
+        match input[..sign + digits.len()].parse() {
+            Ok(value) => Ok((value, rest)),
+            Err(_) => Err(Failure::new(input, "number out of range")),
+        }
+    }
+}
+
+pub fn map<'a, T, U>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    f: impl Fn(T) -> U,
+) -> impl Fn(&'a str) -> PResult<'a, U> {
+    move |input: &'a str| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+/// Maps the parsed value with a fallible `f`, reporting its error where `parser` started.
+pub fn map_res<'a, T, U>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    f: impl Fn(T) -> Result<U, String>,
+) -> impl Fn(&'a str) -> PResult<'a, U> {
+    move |input: &'a str| {
+        let (value, rest) = parser(input)?;
+        match f(value) {
+            Ok(value) => Ok((value, rest)),
+            Err(message) => Err(Failure::new(input, message)),
+        }
+    }
+}
+
+pub fn pair<'a, A, B>(
+    first: impl Fn(&'a str) -> PResult<'a, A>,
+    second: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, (A, B)> {
+    move |input: &'a str| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+pub fn preceded<'a, A, B>(
+    prefix: impl Fn(&'a str) -> PResult<'a, A>,
+    parser: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, B> {
+    map(pair(prefix, parser), |(_, b)| b)
+}
+
+pub fn terminated<'a, A, B>(
+    parser: impl Fn(&'a str) -> PResult<'a, A>,
+    suffix: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, A> {
+    map(pair(parser, suffix), |(a, _)| a)
+}
+
+pub fn opt<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Option<T>> {
+    move |input: &'a str| match parser(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+/// Tries `first` and then `second`, reporting the failure that got further into the input.
+pub fn or<'a, T>(
+    first: impl Fn(&'a str) -> PResult<'a, T>,
+    second: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input: &'a str| {
+        first(input).or_else(|first| {
+            second(input).map_err(|second| match second.at.len() < first.at.len() {
+                true => second,
+                false => first,
+            })
+        })
+    }
+}
+
+/// One or more repetitions of `parser`.
+pub fn many1<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (first, mut rest) = parser(input)?;
+        let mut values = vec![first];
+
+        while let Ok((value, next)) = parser(rest) {
+            if next.len() == rest.len() {
+                break;
+            }
+            values.push(value);
+            rest = next;
+        }
+
+        Ok((values, rest))
+    }
+}
+
+/// One or more `item`s separated by `separator`.
+pub fn separated<'a, T, S>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+    separator: impl Fn(&'a str) -> PResult<'a, S>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (first, mut rest) = item(input)?;
+        let mut values = vec![first];
+
+        while let Ok((_, next)) = separator(rest) {
+            let (value, next) = item(next)?;
+            values.push(value);
+            rest = next;
+        }
+
+        Ok((values, rest))
+    }
+}
+
+pub fn key_value<'a, K, S, V>(
+    key: impl Fn(&'a str) -> PResult<'a, K>,
+    separator: impl Fn(&'a str) -> PResult<'a, S>,
+    value: impl Fn(&'a str) -> PResult<'a, V>,
+) -> impl Fn(&'a str) -> PResult<'a, (K, V)> {
+    pair(key, preceded(separator, value))
+}
+
+/// Parses every line with `item`, which has to consume the whole line.
+pub fn lines<'a, T>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input: &'a str| {
+        let values = input
+            .lines()
+            .map(|line| complete(&item, line))
+            .collect::<Result<_, _>>()?;
+
+        rest(input).map(|(_, rest)| (values, rest))
+    }
+}
+
+/// Parses every block of lines separated by a blank line with `item`, which has to consume the
+/// whole block.
+pub fn blocks<'a, T>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input: &'a str| {
+        let values = split_blocks(input)
+            .map(|block| complete(&item, block))
+            .collect::<Result<_, _>>()?;
+
+        rest(input).map(|(_, rest)| (values, rest))
+    }
+}
+
+/// Blocks of lines separated by one or more blank lines, which may contain whitespace.
+pub fn split_blocks(input: &str) -> Blocks<'_> {
+    Blocks { rest: input }
+}
+
+/// Iterator returned by [`split_blocks`]. Blocks are slices of the input without the line ending
+/// of their last line.
+pub struct Blocks<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut start = None;
+        let mut end = 0;
+        let mut offset = 0;
+
+        for line in self.rest.split_inclusive('\n') {
+            match (start, line.trim().is_empty()) {
+                (Some(_), true) => break,
+                (None, true) => {}
+                (_, false) => {
+                    start.get_or_insert(offset);
+                    end = offset + line.trim_end_matches(['\r', '\n']).len();
+                }
+            }
+            offset += line.len();
+        }
+
+        let block = &self.rest[start?..end];
+        self.rest = &self.rest[offset..];
+        Some(block)
+    }
+}
+
+fn complete<'a, T>(
+    item: impl Fn(&'a str) -> PResult<'a, T>,
+    input: &'a str,
+) -> Result<T, Failure<'a>> {
+    let (value, rest) = item(input)?;
+    end(rest)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers() {
+        assert_eq!(parse("-12", integer::<i32>()), Ok(-12));
+        assert_eq!(parse("+7", integer::<i32>()), Ok(7));
+        assert!(parse("-", integer::<i32>()).is_err());
+        assert!(parse("300", integer::<u8>()).is_err());
+    }
+
+    #[test]
+    fn key_values() {
+        let field = key_value(
+            take_while1(char::is_alphabetic, "a key"),
+            literal(":"),
+            integer(),
+        );
+        let record = separated(field, literal(" "));
+
+        assert_eq!(parse("a:1 b:-2", record), Ok(vec![("a", 1), ("b", -2)]));
+    }
+
+    #[test]
+    fn blocks_of_lines() {
+        let input = "1\n2\n\n3\n";
+
+        assert_eq!(
+            parse(input, blocks(lines(integer::<u8>()))),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+    }
+
+    #[test]
+    fn splits_blocks() {
+        let input = "\n\na\nb\r\n \n\t\nc\n\n";
+        assert_eq!(split_blocks(input).collect::<Vec<_>>(), ["a\nb", "c"]);
+        assert_eq!(split_blocks("a").collect::<Vec<_>>(), ["a"]);
+        assert_eq!(split_blocks(" \n").next(), None);
+    }
+
+    #[test]
+    fn error_position() {
+        let err = parse("1\n2\nx\n", lines(integer::<u8>())).unwrap_err();
+
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.message, "expected a number");
+    }
+
+    #[test]
+    fn or_reports_furthest_failure() {
+        let parser = or(literal("abc"), preceded(literal("a"), literal("x")));
+        let err = parse("abd", parser).unwrap_err();
+
+        assert_eq!(err.column, 2);
+    }
+}
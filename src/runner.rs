@@ -0,0 +1,325 @@
+use crate::json::Value;
+use crate::{AocError, Day, Part};
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Result of parsing the input once and solving the requested parts on it.
+pub struct Run {
+    pub parse_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<String, AocError>,
+    pub time: Duration,
+}
+
+pub fn run(day: &Day, input: &str, parts: &[Part]) -> Result<Run, AocError> {
+    let (parsed, parse_time) = measure(|| day.solution.parse(input));
+    let parsed = parsed?;
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let (answer, time) = measure(|| day.solution.solve(&*parsed, part));
+            PartRun { part, answer, time }
+        })
+        .collect();
+
+    Ok(Run { parse_time, parts })
+}
+
+impl Run {
+    /// One object per part for `--output json`.
+    pub fn to_json(&self, day: &Day) -> Vec<Value> {
+        self.parts
+            .iter()
+            .map(|part| {
+                part_json(day, part.part, Some(self.parse_time), Some(part.time))
+                    .with("answer", part.answer.as_ref().ok().cloned())
+                    .with(
+                        "error",
+                        part.answer.as_ref().err().map(|err| err.to_string()),
+                    )
+            })
+            .collect()
+    }
+}
+
+/// Objects for `--output json` when the input couldn't be parsed.
+pub fn failed_json(day: &Day, parts: &[Part], err: &AocError) -> Vec<Value> {
+    parts
+        .iter()
+        .map(|&part| {
+            part_json(day, part, None, None)
+                .with("answer", Value::Null)
+                .with("error", err.to_string())
+        })
+        .collect()
+}
+
+fn part_json(day: &Day, part: Part, parse_time: Option<Duration>, time: Option<Duration>) -> Value {
+    Value::Object(Vec::new())
+        .with("year", day.year)
+        .with("day", day.day)
+        .with("part", part.number())
+        .with("parse_time_ns", parse_time.map(|time| time.as_nanos()))
+        .with("solve_time_ns", time.map(|time| time.as_nanos()))
+}
+
+/// Answers of every implementation of a part for the same input.
+pub struct CrossCheck {
+    pub part: Part,
+    pub answers: Vec<(&'static str, Result<String, AocError>)>,
+}
+
+impl CrossCheck {
+    /// All implementations found the same answer, or all of them failed.
+    pub fn agrees(&self) -> bool {
+        let answer = |(_, answer): &(_, Result<String, AocError>)| answer.as_ref().ok().cloned();
+        let first = self.answers.first().map(answer);
+        self.answers.iter().all(|a| Some(answer(a)) == first)
+    }
+}
+
+pub fn cross_check(day: &Day, input: &str, parts: &[Part]) -> Result<Vec<CrossCheck>, AocError> {
+    let parsed = day.solution.parse(input)?;
+
+    Ok(parts
+        .iter()
+        .map(|&part| CrossCheck {
+            part,
+            answers: day.solution.solve_all(&*parsed, part),
+        })
+        .collect())
+}
+
+/// Calls `f` on every item using `threads` worker threads, which take the next unprocessed item
+/// whenever they finish one. Results keep the order of `items`.
+pub fn parallel<T, R>(items: &[T], threads: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let item = match items.get(i) {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every item was processed"))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    /// Panics when there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        samples.sort_unstable();
+
+        let total: Duration = samples.iter().sum();
+        let median = match samples.len() {
+            n if n % 2 == 0 => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+            n => samples[n / 2],
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: total / samples.len() as u32,
+        }
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, mean {:.2?}",
+            self.min, self.median, self.mean
+        )
+    }
+}
+
+pub struct Bench {
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+    /// Alternative implementations of the parts, with their names.
+    pub alternatives: Vec<(Part, &'static str, Stats)>,
+}
+
+/// Repeats parsing and every part, including its alternatives, `iterations` times.
+pub fn bench(day: &Day, input: &str, parts: &[Part], iterations: usize) -> Result<Bench, AocError> {
+    let mut parsed: Option<Box<dyn Any>> = None;
+    let mut samples = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations.max(1) {
+        let (result, time) = measure(|| day.solution.parse(input));
+        parsed = Some(result?);
+        samples.push(time);
+    }
+
+    let parsed = parsed.expect("parsed at least once");
+    let parse = Stats::from_samples(samples);
+
+    let sample = |solve: &dyn Fn() -> Result<String, AocError>| {
+        let samples = (0..iterations.max(1)).map(|_| measure(solve).1).collect();
+        Stats::from_samples(samples)
+    };
+
+    let mut alternatives = Vec::new();
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            for name in day.solution.alternatives(part) {
+                let solve = || {
+                    day.solution
+                        .solve_alternative(&*parsed, part, name)
+                        .expect("listed alternative")
+                };
+                alternatives.push((part, name, sample(&solve)));
+            }
+
+            (part, sample(&|| day.solution.solve(&*parsed, part)))
+        })
+        .collect();
+
+    Ok(Bench {
+        parse,
+        parts,
+        alternatives,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let day = crate::find_day(2020, 1).unwrap();
+        let run = Run {
+            parse_time: Duration::from_micros(3),
+            parts: vec![
+                PartRun {
+                    part: Part::One,
+                    answer: Ok("514579".to_owned()),
+                    time: Duration::from_nanos(120),
+                },
+                PartRun {
+                    part: Part::Two,
+                    answer: Err(AocError::NoSolution("none")),
+                    time: Duration::from_nanos(80),
+                },
+            ],
+        };
+
+        let lines: Vec<_> = run.to_json(day).iter().map(Value::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"year":2020,"day":1,"part":1,"parse_time_ns":3000,"solve_time_ns":120,"answer":"514579","error":null}"#,
+                r#"{"year":2020,"day":1,"part":2,"parse_time_ns":3000,"solve_time_ns":80,"answer":null,"error":"no solution found: none"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn cross_check_on_example() {
+        let day = crate::find_day(2020, 8).unwrap();
+        let example = include_str!("../examples/2020_08_1.txt");
+
+        let checks = cross_check(day, example, &[Part::Two]).unwrap();
+        assert_eq!(checks[0].answers.len(), 2);
+        assert!(checks[0].agrees());
+
+        let disagreement = CrossCheck {
+            part: Part::One,
+            answers: vec![
+                ("main", Ok("1".to_owned())),
+                ("other", Err(AocError::NoSolution("none"))),
+            ],
+        };
+        assert!(!disagreement.agrees());
+    }
+
+    #[test]
+    fn solves_generated_inputs() {
+        for day in crate::days() {
+            for seed in 1..=5 {
+                let input = match day.solution.generate(&mut crate::Rng::new(seed)) {
+                    Some(input) => input,
+                    None => break,
+                };
+
+                let context = format!("{} day {} seed {}", day.year, day.day, seed);
+                let answers = |input: &str| {
+                    let run = run(day, input, &[Part::One, Part::Two]).unwrap();
+                    run.parts
+                        .into_iter()
+                        .map(|part| part.answer.ok())
+                        .collect::<Vec<_>>()
+                };
+
+                let expected = answers(&input);
+                assert!(expected.iter().all(Option::is_some), "{}", context);
+
+                let windows = format!("\u{feff}{}\r\n", input.replace('\n', "\r\n"));
+                assert_eq!(answers(&windows), expected, "{} with CRLF", context);
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_keeps_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let squares = parallel(&items, 4, |&n| {
+            std::thread::sleep(Duration::from_micros(50 - n));
+            n * n
+        });
+
+        assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+        assert_eq!(parallel(&[] as &[u8], 4, |&n| n), []);
+    }
+
+    #[test]
+    fn stats() {
+        let samples = [4, 1, 3, 2].iter().map(|&ms| Duration::from_millis(ms));
+        let stats = Stats::from_samples(samples.collect());
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+    }
+}
@@ -0,0 +1,221 @@
+//! `aoc new`: generates the source file of a day and registers it.
+//!
+//! The registry of a year (`src/<year>/mod.rs`) is regenerated from the `pub mod dayNN;`
+//! declarations it contains, so it shouldn't hold anything else. A year seen for the first time
+//! also gets added to the module declarations and `YEARS` in `src/lib.rs`.
+
+use crate::cli::UsageError;
+use crate::AocError;
+use std::path::{Path, PathBuf};
+
+/// Creates the files of a new day under `root` and returns the paths that were written.
+///
+/// Refuses to touch days that already have a source file, while input and example files that
+/// already exist are kept as they are.
+pub fn new_day(root: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, AocError> {
+    if year < 2015 || !(1..=25).contains(&day) {
+        return Err(UsageError(format!("there is no puzzle for {} day {}", year, day)).into());
+    }
+
+    let year_dir = root.join("src").join(year.to_string());
+    let source = year_dir.join(format!("day{:02}.rs", day));
+    let registry = year_dir.join("mod.rs");
+
+    if source.exists() {
+        return Err(UsageError(format!(
+            "{} day {} already exists in {}",
+            year,
+            day,
+            source.display()
+        ))
+        .into());
+    }
+
+    let mut days = match registry.exists() {
+        true => registered_days(&read(&registry)?),
+        false => {
+            let lib = root.join("src").join("lib.rs");
+            write(&lib, &register_year(&read(&lib)?, year))?;
+            Vec::new()
+        }
+    };
+    days.push(day);
+    days.sort_unstable();
+
+    let mut created = vec![source.clone(), registry.clone()];
+    write(&source, &day_source(year, day))?;
+    write(&registry, &registry_source(year, &days))?;
+
+    for path in [
+        root.join(crate::input::default_path(year, day)),
+        root.join(format!("examples/{}_{:02}_1.txt", year, day)),
+    ] {
+        if !path.exists() {
+            write(&path, "")?;
+            created.push(path);
+        }
+    }
+
+    Ok(created)
+}
+
+fn day_source(year: u16, day: u8) -> String {
+    TEMPLATE
+        .replace("{name}", &format!("Day{:02}", day))
+        .replace("{example}", &format!("{}_{:02}_1", year, day))
+}
+
+const TEMPLATE: &str = r#"use crate::prelude::*;
+use crate::Solution;
+
+pub struct {name};
+
+impl Solution for {name} {
+    type Input = Vec<i64>;
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(parser::parse(input, parser::lines(parser::integer()))?)
+    }
+
+    fn part1(_input: &Self::Input) -> Result<Self::Answer, AocError> {
+        Err(AocError::NoSolution("part 1 isn't solved yet"))
+    }
+
+    fn part2(_input: &Self::Input) -> Result<Self::Answer, AocError> {
+        Err(AocError::NoSolution("part 2 isn't solved yet"))
+    }
+}
+
+// crate::examples!({name} {
+//     part1: ("{example}", One, 0),
+// });
+"#;
+
+/// Days declared as `pub mod dayNN;` in a year's registry.
+fn registered_days(registry: &str) -> Vec<u8> {
+    registry
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub mod day")?.strip_suffix(';'))
+        .filter_map(|day| day.parse().ok())
+        .collect()
+}
+
+fn registry_source(year: u16, days: &[u8]) -> String {
+    let mut source = String::from("use crate::Day;\n\n");
+
+    for day in days {
+        source += &format!("pub mod day{:02};\n", day);
+    }
+
+    let entries: Vec<_> = days
+        .iter()
+        .map(|day| {
+            format!(
+                "Day {{\n    year: {},\n    day: {},\n    solution: &day{:02}::Day{:02},\n}}",
+                year, day, day, day
+            )
+        })
+        .collect();
+
+    // Formatted the way rustfmt would, which keeps a single entry on the opening line.
+    match entries.as_slice() {
+        [entry] => source += &format!("\npub const DAYS: &[Day] = &[{}];\n", entry),
+        _ => {
+            source += "\npub const DAYS: &[Day] = &[\n";
+            for entry in &entries {
+                for line in format!("{},", entry).lines() {
+                    source += &format!("    {}\n", line);
+                }
+            }
+            source += "];\n";
+        }
+    }
+
+    source
+}
+
+/// Adds the module of `year` and its registry to `lib.rs`, keeping years in order.
+fn register_year(lib: &str, year: u16) -> String {
+    let mut years = declared_years(lib);
+    let position = years.iter().position(|&y| y > year);
+    years.insert(position.unwrap_or(years.len()), year);
+
+    let declaration = format!("#[path = \"{0}/mod.rs\"]\npub mod y{0};\n", year);
+    let anchor = match position {
+        Some(i) => lib.find(&format!("#[path = \"{}/mod.rs\"]", years[i + 1])),
+        None => {
+            let last = format!("pub mod y{};\n", years[years.len() - 2]);
+            lib.find(&last).map(|i| i + last.len())
+        }
+    }
+    .expect("lib.rs declares every year module");
+
+    let mut lib = format!("{}{}{}", &lib[..anchor], declaration, &lib[anchor..]);
+
+    let start = lib.find(YEARS).expect("lib.rs declares YEARS") + YEARS.len();
+    let end = start + lib[start..].find("];").expect("YEARS is terminated");
+    let list: Vec<_> = years.iter().map(|y| format!("y{}::DAYS", y)).collect();
+    lib.replace_range(start..end, &list.join(", "));
+
+    lib
+}
+
+const YEARS: &str = "const YEARS: &[&[Day]] = &[";
+
+fn declared_years(lib: &str) -> Vec<u16> {
+    lib.lines()
+        .filter_map(|line| line.strip_prefix("pub mod y")?.strip_suffix(';'))
+        .filter_map(|year| year.parse().ok())
+        .collect()
+}
+
+fn read(path: &Path) -> Result<String, AocError> {
+    std::fs::read_to_string(path)
+        .map_err(|err| AocError::io(format!("couldn't read {}", path.display()), err))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), AocError> {
+    let context = || format!("couldn't write {}", path.display());
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| AocError::io(context(), err))?;
+    }
+    std::fs::write(path, content).map_err(|err| AocError::io(context(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_round_trip() {
+        let registry = registry_source(2020, &[1, 8]);
+
+        assert_eq!(registered_days(&registry), [1, 8]);
+        assert!(registry.contains("pub mod day08;\n"));
+        assert!(registry.contains("    solution: &day08::Day08,\n"));
+    }
+
+    #[test]
+    fn registers_years_in_order() {
+        let lib = "#[path = \"2019/mod.rs\"]\npub mod y2019;\n#[path = \"2020/mod.rs\"]\npub mod y2020;\n\n\
+                   const YEARS: &[&[Day]] = &[y2019::DAYS, y2020::DAYS];\n";
+
+        let lib = register_year(lib, 2021);
+        let lib = register_year(&lib, 2015);
+
+        assert_eq!(declared_years(&lib), [2015, 2019, 2020, 2021]);
+        assert!(lib.contains(
+            "const YEARS: &[&[Day]] = &[y2015::DAYS, y2019::DAYS, y2020::DAYS, y2021::DAYS];"
+        ));
+    }
+
+    #[test]
+    fn refuses_existing_days() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        assert!(matches!(new_day(root, 2020, 8), Err(AocError::Usage(_))));
+        assert!(matches!(new_day(root, 2020, 26), Err(AocError::Usage(_))));
+    }
+}
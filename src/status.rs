@@ -0,0 +1,111 @@
+//! `aoc status`: an advent calendar of every year with a registered day.
+
+use crate::answers::Answers;
+use crate::{AocError, Part};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStatus {
+    pub day: u8,
+    pub solved: bool,
+    /// Whether parts one and two have a recorded answer.
+    pub answers: [bool; 2],
+    pub examples: usize,
+}
+
+impl DayStatus {
+    pub fn load(year: u16, day: u8) -> Result<DayStatus, AocError> {
+        let answers = Answers::load(year, day)?;
+
+        Ok(DayStatus {
+            day,
+            solved: crate::find_day(year, day).is_some(),
+            answers: [
+                answers.get(Part::One).is_some(),
+                answers.get(Part::Two).is_some(),
+            ],
+            examples: crate::input::example_paths(year, day).len(),
+        })
+    }
+
+    /// `*` for a recorded answer, `o` for a part that is solved without one.
+    fn marks(&self) -> String {
+        let mut marks: String = self
+            .answers
+            .iter()
+            .map(|&answer| match (answer, self.solved) {
+                (true, _) => '*',
+                (false, true) => 'o',
+                (false, false) => ' ',
+            })
+            .collect();
+
+        marks.push(if self.examples > 0 { 'e' } else { ' ' });
+        marks
+    }
+}
+
+/// Years with at least one registered day, in order.
+pub fn years() -> Vec<u16> {
+    let mut years: Vec<_> = crate::days().map(|day| day.year).collect();
+    years.dedup();
+    years
+}
+
+pub fn calendar(year: u16, days: &[DayStatus]) -> String {
+    let stars: usize = days
+        .iter()
+        .map(|day| day.answers.iter().filter(|&&answer| answer).count())
+        .sum();
+    let separator = format!("{}+\n", "+--------".repeat(5));
+
+    let mut calendar = format!("{:<30}{:>16}\n", year, format!("{} stars", stars));
+    calendar += &separator;
+
+    for week in (1..=25).collect::<Vec<u8>>().chunks(5) {
+        for &day in week {
+            let marks = days
+                .iter()
+                .find(|status| status.day == day)
+                .map_or_else(|| "   ".to_owned(), DayStatus::marks);
+            calendar += &format!("| {:>2} {} ", day, marks);
+        }
+        calendar += "|\n";
+        calendar += &separator;
+    }
+
+    calendar
+}
+
+pub const LEGEND: &str = "* answer recorded  o solved, no recorded answer  e has examples";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_calendar() {
+        let days = [
+            DayStatus {
+                day: 1,
+                solved: true,
+                answers: [true, true],
+                examples: 1,
+            },
+            DayStatus {
+                day: 7,
+                solved: true,
+                answers: [true, false],
+                examples: 0,
+            },
+        ];
+
+        let calendar = calendar(2020, &days);
+        let lines: Vec<_> = calendar.lines().collect();
+
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "2020                                   3 stars");
+        assert_eq!(lines[1], "+--------+--------+--------+--------+--------+");
+        assert_eq!(lines[2], "|  1 **e |  2     |  3     |  4     |  5     |");
+        assert_eq!(lines[4], "|  6     |  7 *o  |  8     |  9     | 10     |");
+    }
+}
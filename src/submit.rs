@@ -0,0 +1,345 @@
+//! `aoc submit`: posts answers to the puzzle server and keeps a history of the outcomes in
+//! `submissions/<year>_<day>.txt`, so no answer gets submitted twice.
+
+use crate::diagnostic::ParseError;
+use crate::http::Client;
+use crate::{AocError, Part};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong without a hint whether the answer is too high or too low.
+    Wrong,
+    /// Submitted too soon after the previous answer.
+    Wait(Duration),
+    /// The part was solved already, the answer wasn't checked.
+    AlreadySolved,
+}
+
+impl Outcome {
+    /// Recognizes the message in a response of the server.
+    pub fn classify(response: &str) -> Option<Outcome> {
+        if response.contains("That's the right answer") {
+            Some(Outcome::Correct)
+        } else if response.contains("answer is too high") {
+            Some(Outcome::TooHigh)
+        } else if response.contains("answer is too low") {
+            Some(Outcome::TooLow)
+        } else if response.contains("That's not the right answer") {
+            Some(Outcome::Wrong)
+        } else if response.contains("You gave an answer too recently") {
+            Some(Outcome::Wait(
+                wait_time(response).unwrap_or(Duration::from_secs(60)),
+            ))
+        } else if response.contains("You don't seem to be solving the right level") {
+            Some(Outcome::AlreadySolved)
+        } else {
+            None
+        }
+    }
+}
+
+/// Time from `You have 1m 5s left to wait.`
+fn wait_time(response: &str) -> Option<Duration> {
+    let start = response.find("You have ")? + "You have ".len();
+    let end = start + response[start..].find(" left to wait")?;
+
+    response[start..end]
+        .split_whitespace()
+        .map(|amount| {
+            let (number, unit) = amount.split_at(amount.len().checked_sub(1)?);
+            let seconds = match unit {
+                "h" => 3600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+            Some(number.parse::<u64>().ok()? * seconds)
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "too high"),
+            Outcome::TooLow => write!(f, "too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::Wait(time) => write!(f, "wait {}s", time.as_secs()),
+            Outcome::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+const RECORDED_OUTCOMES: [Outcome; 5] = [
+    Outcome::Correct,
+    Outcome::TooHigh,
+    Outcome::TooLow,
+    Outcome::Wrong,
+    Outcome::AlreadySolved,
+];
+
+/// Submissions of a day, one `part<n>: <answer> <outcome>` line per submitted answer and a
+/// `wait-until: <unix time>` line while the server doesn't accept answers.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct History {
+    pub submissions: Vec<(Part, String, Outcome)>,
+    pub wait_until: Option<u64>,
+}
+
+impl History {
+    pub fn path(year: u16, day: u8) -> PathBuf {
+        PathBuf::from(format!("submissions/{}_{:02}.txt", year, day))
+    }
+
+    /// A missing history file means nothing was submitted yet.
+    pub fn load(path: &Path) -> Result<History, AocError> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(content.parse()?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(AocError::io(
+                format!("couldn't read {}", path.display()),
+                err,
+            )),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AocError> {
+        let context = || format!("couldn't write {}", path.display());
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| AocError::io(context(), err))?;
+        }
+        std::fs::write(path, self.to_string()).map_err(|err| AocError::io(context(), err))
+    }
+
+    /// Outcome of an earlier submission of `answer`, or `AlreadySolved` once the part is solved.
+    pub fn known(&self, part: Part, answer: &str) -> Option<Outcome> {
+        let mut submissions = self.submissions.iter().filter(|(p, ..)| *p == part);
+
+        if submissions
+            .clone()
+            .any(|(_, _, outcome)| *outcome == Outcome::Correct)
+        {
+            return Some(Outcome::AlreadySolved);
+        }
+
+        submissions
+            .find(|(_, a, _)| a == answer)
+            .map(|(_, _, outcome)| outcome.clone())
+    }
+}
+
+impl std::str::FromStr for History {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut history = History::default();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = crate::prelude::split_once(line, ": ")
+                .ok_or_else(|| ParseError::at(s, line, "expected '<key>: <value>'"))?;
+
+            let part = match key {
+                "part1" => Part::One,
+                "part2" => Part::Two,
+                "wait-until" => {
+                    let time = value
+                        .parse()
+                        .map_err(|_| ParseError::at(s, value, "expected a unix time"))?;
+                    history.wait_until = Some(time);
+                    continue;
+                }
+                _ => return Err(ParseError::at(s, key, format!("unknown key '{}'", key))),
+            };
+
+            // Answers may contain spaces, so the outcome is recognized at the end of the line.
+            let (answer, outcome) = RECORDED_OUTCOMES
+                .iter()
+                .find_map(|outcome| {
+                    let answer = value.strip_suffix(&outcome.to_string())?;
+                    Some((answer.strip_suffix(' ')?, outcome.clone()))
+                })
+                .ok_or_else(|| ParseError::at(s, value, "expected '<answer> <outcome>'"))?;
+
+            history.submissions.push((part, answer.to_owned(), outcome));
+        }
+
+        Ok(history)
+    }
+}
+
+impl std::fmt::Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (part, answer, outcome) in &self.submissions {
+            writeln!(f, "part{}: {} {}", part.number(), answer, outcome)?;
+        }
+        if let Some(time) = self.wait_until {
+            writeln!(f, "wait-until: {}", time)?;
+        }
+        Ok(())
+    }
+}
+
+/// Submits `answer` unless the history already knows its outcome, waiting for as long as the
+/// server asks to.
+pub fn submit(
+    client: &Client,
+    history_path: &Path,
+    year: u16,
+    day: u8,
+    part: Part,
+    answer: &str,
+) -> Result<Outcome, AocError> {
+    let mut history = History::load(history_path)?;
+
+    if let Some(outcome) = history.known(part, answer) {
+        return Ok(outcome);
+    }
+
+    let form = format!("level={}&answer={}", part.number(), url_encode(answer));
+
+    loop {
+        if let Some(remaining) = history
+            .wait_until
+            .and_then(|until| Duration::from_secs(until).checked_sub(unix_time()))
+        {
+            std::thread::sleep(remaining);
+        }
+
+        let path = format!("/{}/day/{}/answer", year, day);
+        let response = client.post(&path, &form)?;
+        let outcome = Outcome::classify(&response).ok_or_else(|| {
+            AocError::io(
+                format!("couldn't submit {} day {} {}", year, day, part),
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "the server responded with an unknown message",
+                ),
+            )
+        })?;
+
+        match outcome {
+            Outcome::Wait(time) => {
+                history.wait_until = Some((unix_time() + time).as_secs());
+                history.save(history_path)?;
+            }
+            outcome => {
+                history.wait_until = None;
+                history
+                    .submissions
+                    .push((part, answer.to_owned(), outcome.clone()));
+                history.save(history_path)?;
+                return Ok(outcome);
+            }
+        }
+    }
+}
+
+fn unix_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{stand_in, stand_in_client};
+
+    #[test]
+    fn classify() {
+        let cases = [
+            (
+                "<p>That's the right answer! You are one gold star closer.</p>",
+                Outcome::Correct,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too high.</p>",
+                Outcome::TooHigh,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too low.</p>",
+                Outcome::TooLow,
+            ),
+            (
+                "<p>That's not the right answer. If you're stuck...</p>",
+                Outcome::Wrong,
+            ),
+            (
+                "<p>You gave an answer too recently. You have 1m 5s left to wait.</p>",
+                Outcome::Wait(Duration::from_secs(65)),
+            ),
+            (
+                "<p>You don't seem to be solving the right level. Did you already complete it?</p>",
+                Outcome::AlreadySolved,
+            ),
+        ];
+
+        for (response, outcome) in cases.iter() {
+            assert_eq!(Outcome::classify(response).as_ref(), Some(outcome));
+        }
+        assert_eq!(Outcome::classify("<html></html>"), None);
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let text = "part1: 12 too low\npart1: 15 correct\npart2: a b wrong\nwait-until: 100\n";
+        let history: History = text.parse().unwrap();
+
+        assert_eq!(history.to_string(), text);
+        assert_eq!(history.known(Part::One, "12"), Some(Outcome::AlreadySolved));
+        assert_eq!(history.known(Part::Two, "a b"), Some(Outcome::Wrong));
+        assert_eq!(history.known(Part::Two, "c"), None);
+    }
+
+    #[test]
+    fn waits_and_never_resubmits() {
+        let (url, server) = stand_in(vec![
+            (
+                200,
+                "<p>You gave an answer too recently. You have 1s left to wait.</p>",
+            ),
+            (
+                200,
+                "<p>That's not the right answer; your answer is too high.</p>",
+            ),
+        ]);
+        let (client, dir) = stand_in_client("submit", url);
+        let history = dir.join("2020_01.txt");
+
+        for _ in 0..2 {
+            let outcome = submit(&client, &history, 2020, 1, Part::Two, "4 2").unwrap();
+            assert_eq!(outcome, Outcome::TooHigh);
+        }
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].starts_with("POST /2020/day/1/answer "));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=2&answer=4%202"));
+        assert_eq!(
+            std::fs::read_to_string(&history).unwrap(),
+            "part2: 4 2 too high\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
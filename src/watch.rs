@@ -0,0 +1,155 @@
+//! `aoc watch`: re-runs a day whenever its input or one of its examples changes.
+//!
+//! Changes are found by polling modification times, so it works everywhere without
+//! platform specific notification APIs. Changes to the source need a rebuild and restart.
+
+use crate::{runner, Day, Part};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Input file of the day and all of its examples.
+pub fn day_files(year: u16, day: u8) -> Vec<PathBuf> {
+    let mut files = vec![crate::input::default_path(year, day)];
+    files.extend(crate::input::example_paths(year, day));
+    files
+}
+
+/// Remembers the modification time and size of the watched files.
+#[derive(Debug, Default)]
+pub struct Watcher {
+    seen: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl Watcher {
+    /// Whether any of `files` was added, removed or modified since the last poll. The first poll
+    /// always reports a change.
+    pub fn poll(&mut self, files: Vec<PathBuf>) -> bool {
+        let current: Vec<_> = files
+            .into_iter()
+            .map(|path| {
+                let stamp = std::fs::metadata(&path)
+                    .ok()
+                    .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+                (path, stamp)
+            })
+            .collect();
+
+        let changed = self.seen.is_empty() || current != self.seen;
+        self.seen = current;
+        changed
+    }
+}
+
+/// Answer of a part for one of the watched files, or the error that prevented it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub file: String,
+    pub part: Part,
+    pub result: Result<String, String>,
+}
+
+/// Solves both parts for every file.
+pub fn evaluate(day: &Day, files: &[PathBuf]) -> Vec<Answer> {
+    let mut answers = Vec::new();
+
+    for path in files {
+        let file = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+
+        let run = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|input| {
+                runner::run(day, &input, &[Part::One, Part::Two]).map_err(|err| first_line(&err))
+            });
+
+        match run {
+            Ok(run) => answers.extend(run.parts.into_iter().map(|part| Answer {
+                file: file.clone(),
+                part: part.part,
+                result: part.answer.map_err(|err| first_line(&err)),
+            })),
+            Err(err) => answers.extend([Part::One, Part::Two].iter().map(|&part| Answer {
+                file: file.clone(),
+                part,
+                result: Err(err.clone()),
+            })),
+        }
+    }
+
+    answers
+}
+
+fn first_line(err: &impl std::fmt::Display) -> String {
+    err.to_string().lines().next().unwrap_or("").to_owned()
+}
+
+/// One line per answer, noting what it was in `previous` when it changed.
+pub fn report(previous: &[Answer], current: &[Answer]) -> Vec<String> {
+    current
+        .iter()
+        .map(|answer| {
+            let show = |result: &Result<String, String>| match result {
+                Ok(value) => value.clone(),
+                Err(err) => format!("ERROR {}", err),
+            };
+
+            let line = format!("{} {}: {}", answer.file, answer.part, show(&answer.result));
+            let before = previous
+                .iter()
+                .find(|a| a.file == answer.file && a.part == answer.part);
+
+            match before {
+                Some(before) if before.result != answer.result => {
+                    format!("{} (was {})", line, show(&before.result))
+                }
+                _ => line,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::scratch_dir;
+
+    #[test]
+    fn detects_changes() {
+        let dir = scratch_dir("watch");
+        let file = dir.join("input.txt");
+        std::fs::write(&file, "1").unwrap();
+
+        let mut watcher = Watcher::default();
+        assert!(watcher.poll(vec![file.clone()]));
+        assert!(!watcher.poll(vec![file.clone()]));
+
+        std::fs::write(&file, "12").unwrap();
+        assert!(watcher.poll(vec![file.clone()]));
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(watcher.poll(vec![file]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_differences() {
+        let answer = |part, result: Result<&str, &str>| Answer {
+            file: "2020_01.txt".to_owned(),
+            part,
+            result: result.map(str::to_owned).map_err(str::to_owned),
+        };
+        let previous = [answer(Part::One, Ok("1")), answer(Part::Two, Ok("2"))];
+        let current = [answer(Part::One, Ok("1")), answer(Part::Two, Err("oops"))];
+
+        assert_eq!(
+            report(&previous, &current),
+            [
+                "2020_01.txt Part 1: 1",
+                "2020_01.txt Part 2: ERROR oops (was 2)"
+            ]
+        );
+    }
+}